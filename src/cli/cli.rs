@@ -1,5 +1,5 @@
 use crate::commands::{
-    add, clean, clone, discord, info, init, list, login, node, outdated, run, search,
+    add, clean, clone, completions, discord, info, init, list, login, node, outdated, run, search,
 }; // remove outdated later
 use async_trait::async_trait;
 use clap::{
@@ -19,6 +19,7 @@ pub trait VoltCommand {
 pub enum VoltSubCmd {
     Add(add::Add),
     Clone(clone::Clone),
+    Completions(completions::Completions),
     Init(init::Init),
     Clean(clean::Clean),
     Discord(discord::Discord),
@@ -31,12 +32,20 @@ pub enum VoltSubCmd {
     List(list::List),             // remove later???
 }
 
+impl VoltSubCmd {
+    /// Whether the command was asked to only print errors, e.g. `volt node use --silent`
+    pub fn is_silent(&self) -> bool {
+        matches!(self, Self::Node(node) if node.is_silent())
+    }
+}
+
 #[async_trait]
 impl VoltCommand for VoltSubCmd {
     async fn exec(self, config: VoltConfig) -> miette::Result<()> {
         match self {
             Self::Add(x) => x.exec(config).await,
             Self::Clone(x) => x.exec(config).await,
+            Self::Completions(x) => x.exec(config).await,
             Self::Init(x) => x.exec(config).await,
             Self::Clean(x) => x.exec(config).await,
             Self::Discord(x) => x.exec(config).await,
@@ -69,6 +78,8 @@ pub struct VoltCli {
 }
 
 impl VoltCli {
+    /// Parse the command line, this reads the process arguments so isn't a `Default`
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self::parse()
     }
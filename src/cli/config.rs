@@ -16,25 +16,97 @@ limitations under the License.
 
 use crate::core::utils::errors::VoltError;
 
-use clap::{ArgMatches, Parser};
+use clap::{ArgEnum, ArgMatches, Parser};
 use dirs::home_dir;
+use miette::{IntoDiagnostic, WrapErr};
 use package_spec::{parse_package_spec, PackageSpec};
 use sha1::Digest;
 use sha2::Sha512;
 use ssri::{Algorithm, Integrity};
-use std::{env, path::PathBuf};
+use std::{
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// When to color output
+#[derive(Debug, Clone, Copy, PartialEq, ArgEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
 
 #[derive(Debug, Clone, Parser)]
 pub struct VoltConfig {
     /// Path to current working directory
     #[clap(short, long)]
     cwd: Option<PathBuf>,
+
+    /// Base URL of the mirror to download node from, repeat it to add fallback mirrors
+    /// (overridden by `VOLT_NODE_MIRRORS` or `VOLT_NODE_MIRROR`)
+    #[clap(long, alias = "mirror", global = true)]
+    node_mirror: Vec<String>,
+
+    /// Re-download the node release index instead of using the cached copy
+    #[clap(long, global = true)]
+    refresh: bool,
+
+    /// Never touch the network, only use the cached release index and files already on disk
+    /// (also set by `VOLT_OFFLINE`)
+    #[clap(long, global = true, conflicts_with = "refresh")]
+    offline: bool,
+
+    /// Number of seconds a cached node release index is considered fresh
+    #[clap(long, global = true, default_value = "3600")]
+    index_ttl: u64,
+
+    /// npm registry that `volt node use` points the version's npm at (overridden by
+    /// `VOLT_NPM_REGISTRY`)
+    #[clap(long, global = true)]
+    npm_registry: Option<String>,
+
+    /// Proxy to send requests through, instead of `HTTP_PROXY`/`HTTPS_PROXY`
+    #[clap(long, global = true)]
+    proxy: Option<String>,
+
+    /// Don't show progress bars (also set by `VOLT_QUIET`)
+    #[clap(short, long, global = true)]
+    quiet: bool,
+
+    /// Times to retry a request that failed because of a timeout, dropped connection or
+    /// server error
+    #[clap(long, global = true, default_value = "2")]
+    retries: u32,
+
+    /// Seconds to wait for the mirror to respond, or to send the next part of a download
+    #[clap(long, global = true, default_value = "30")]
+    timeout: u64,
+
+    /// Directory to keep node versions and their metadata in (overrides `VOLT_HOME`)
+    #[clap(long, global = true)]
+    data_dir: Option<PathBuf>,
+
+    /// On macos, keep data in `~/.local/share/volt` (or `$XDG_DATA_HOME/volt`) like on linux,
+    /// instead of `~/Library/Application Support/volt` (also set by `VOLT_XDG`)
+    ///
+    /// Versions installed before switching stay in the old directory, move them over with
+    /// `volt node migrate <old dir>`.
+    #[clap(long, global = true)]
+    xdg: bool,
+
+    /// When to color output, `auto` colors terminals unless `NO_COLOR` is set
+    #[clap(long, global = true, arg_enum, default_value = "auto")]
+    color: ColorChoice,
 }
 
 impl VoltConfig {
     pub const OS: &'static str = env::consts::OS;
     pub const VOLT_HOME: &'static str = ".volt";
     pub const VOLT_LOCK: &'static str = "volt.lock";
+    pub const NODE_MIRROR: &'static str = "https://nodejs.org/dist";
+    pub const MAX_REDIRECTS: usize = 5;
 
     pub fn home(&self) -> miette::Result<PathBuf> {
         Ok(dirs::home_dir().ok_or(VoltError::GetHomeDirError)?)
@@ -65,6 +137,188 @@ impl VoltConfig {
         Ok(self.home()?.join(Self::VOLT_HOME))
     }
 
+    /// Whether output should be colored, `None` leaves it to terminal detection
+    pub fn color(&self) -> Option<bool> {
+        match self.color {
+            ColorChoice::Always => Some(true),
+            ColorChoice::Never => Some(false),
+            ColorChoice::Auto if env::var_os("NO_COLOR").map_or(false, |v| !v.is_empty()) => {
+                Some(false)
+            }
+            ColorChoice::Auto => None,
+        }
+    }
+
+    /// Path to the data directory, holding node versions, aliases and the release index
+    ///
+    /// Read from `--data-dir`, then `VOLT_HOME`, then defaults to `volt` in the platform's
+    /// data directory (e.g. `~/.local/share/volt`)
+    pub fn volt_dir(&self) -> miette::Result<PathBuf> {
+        pick_volt_dir(
+            self.data_dir.as_deref(),
+            env::var_os("VOLT_HOME"),
+            self.platform_data_dir(),
+        )
+    }
+
+    /// The platform's data directory, or the XDG one on macos with `--xdg`/`VOLT_XDG`
+    ///
+    /// Linux already uses the XDG directory and windows has no equivalent, so `--xdg` only
+    /// changes anything on macos.
+    fn platform_data_dir(&self) -> Option<PathBuf> {
+        let xdg = self.xdg || env::var_os("VOLT_XDG").map_or(false, |x| !x.is_empty());
+
+        if cfg!(target_os = "macos") && xdg {
+            xdg_data_dir(env::var_os("XDG_DATA_HOME"), dirs::home_dir())
+        } else {
+            dirs::data_dir()
+        }
+    }
+
+    /// Path to the directory installed node versions live in
+    pub fn node_dir(&self) -> miette::Result<PathBuf> {
+        Ok(self.volt_dir()?.join("node"))
+    }
+
+    /// Path to the directory the active node version's binaries are linked into
+    ///
+    /// `~/.local/bin` on unix, `bin` in the data directory on windows
+    pub fn bin_dir(&self) -> miette::Result<PathBuf> {
+        if cfg!(windows) {
+            Ok(self.volt_dir()?.join("bin"))
+        } else {
+            Ok(self.home()?.join(".local").join("bin"))
+        }
+    }
+
+    /// Path to the marker recording the active node version
+    ///
+    /// A symlink to the version's `bin` directory on unix, a file holding the version on windows
+    pub fn current_marker(&self) -> miette::Result<PathBuf> {
+        if cfg!(windows) {
+            Ok(self.volt_dir()?.join("current"))
+        } else {
+            Ok(self.node_dir()?.join("current"))
+        }
+    }
+
+    /// Base URL of the primary node mirror, without a trailing slash
+    pub fn node_mirror(&self) -> miette::Result<String> {
+        Ok(self.node_mirrors()?.remove(0))
+    }
+
+    /// Base URLs of the node mirrors in order of preference, without trailing slashes
+    ///
+    /// Read from the comma-separated `VOLT_NODE_MIRRORS`, then `VOLT_NODE_MIRROR`, then
+    /// `--node-mirror`, then defaults to `https://nodejs.org/dist`. Never empty.
+    pub fn node_mirrors(&self) -> miette::Result<Vec<String>> {
+        let from_env = |name| {
+            env::var(name)
+                .map(|list| {
+                    list.split(',')
+                        .map(str::trim)
+                        .filter(|m| !m.is_empty())
+                        .map(String::from)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut mirrors = from_env("VOLT_NODE_MIRRORS");
+        if mirrors.is_empty() {
+            mirrors = from_env("VOLT_NODE_MIRROR");
+        }
+        if mirrors.is_empty() {
+            mirrors = self.node_mirror.clone();
+        }
+        if mirrors.is_empty() {
+            mirrors.push(Self::NODE_MIRROR.to_string());
+        }
+
+        mirrors
+            .into_iter()
+            .map(|mirror| {
+                reqwest::Url::parse(&mirror).map_err(|e| VoltError::InvalidMirrorUrl {
+                    url: mirror.clone(),
+                    reason: e.to_string(),
+                })?;
+
+                Ok(mirror.trim_end_matches('/').to_string())
+            })
+            .collect()
+    }
+
+    /// Registry to point npm at when switching node versions, if one is configured
+    ///
+    /// Read from `VOLT_NPM_REGISTRY`, then `--npm-registry`.
+    pub fn npm_registry(&self) -> Option<String> {
+        env::var("VOLT_NPM_REGISTRY")
+            .ok()
+            .filter(|registry| !registry.is_empty())
+            .or_else(|| self.npm_registry.clone())
+    }
+
+    /// Whether the cached node release index should be ignored
+    pub fn refresh(&self) -> bool {
+        self.refresh
+    }
+
+    /// Whether `--offline` or `VOLT_OFFLINE` forbid network access
+    pub fn offline(&self) -> bool {
+        self.offline || env::var_os("VOLT_OFFLINE").map_or(false, |o| !o.is_empty())
+    }
+
+    /// How long a cached node release index stays fresh
+    pub fn index_ttl(&self) -> Duration {
+        Duration::from_secs(self.index_ttl)
+    }
+
+    /// Whether to draw progress bars and spinners
+    ///
+    /// Off with `--quiet` or `VOLT_QUIET`, and when stderr (where they're drawn) isn't a terminal,
+    /// e.g. in CI logs
+    pub fn show_progress(&self) -> bool {
+        !self.quiet() && dialoguer::console::Term::stderr().is_term()
+    }
+
+    /// Whether `--quiet` or `VOLT_QUIET` asked for less output
+    pub fn quiet(&self) -> bool {
+        self.quiet || env::var_os("VOLT_QUIET").map_or(false, |q| !q.is_empty())
+    }
+
+    /// Times to retry a request after a transient failure
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// How long to wait on an unresponsive mirror before giving up (or retrying)
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+
+    /// HTTP client to share between requests
+    ///
+    /// Uses `--proxy` if given, otherwise the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY`
+    /// environment variables
+    pub fn http_client(&self) -> miette::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(self.timeout())
+            .redirect(reqwest::redirect::Policy::limited(Self::MAX_REDIRECTS));
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|e| VoltError::InvalidProxyUrl {
+                url: proxy.clone(),
+                reason: e.to_string(),
+            })?;
+            builder = builder.proxy(proxy);
+        }
+
+        builder
+            .build()
+            .into_diagnostic()
+            .wrap_err("failed to set up the HTTP client")
+    }
+
     /// Calculate the hash of a tarball
     ///
     /// ## Examples
@@ -91,3 +345,66 @@ impl VoltConfig {
         Ok(integrity)
     }
 }
+
+/// `XDG_DATA_HOME` if it's an absolute path, otherwise `~/.local/share`, as the XDG spec asks
+fn xdg_data_dir(xdg_data_home: Option<OsString>, home: Option<PathBuf>) -> Option<PathBuf> {
+    xdg_data_home
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| Some(home?.join(".local").join("share")))
+}
+
+/// Where the data directory lives: `--data-dir`, then `VOLT_HOME`, then the platform's
+/// data directory if it has one
+fn pick_volt_dir(
+    flag: Option<&Path>,
+    volt_home: Option<OsString>,
+    platform: Option<PathBuf>,
+) -> miette::Result<PathBuf> {
+    if let Some(dir) = flag {
+        return Ok(dir.to_path_buf());
+    }
+
+    if let Some(dir) = volt_home.filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+
+    Ok(platform.ok_or(VoltError::GetDataDirError)?.join("volt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_data_dir_is_an_error() {
+        // An empty `VOLT_HOME` counts as unset, as it does in the shell
+        let err = pick_volt_dir(None, Some(OsString::new()), None).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("could not determine a data directory"));
+
+        let dir = pick_volt_dir(None, None, Some(PathBuf::from("/data"))).unwrap();
+        assert_eq!(dir, Path::new("/data/volt"));
+        let dir = pick_volt_dir(None, Some("/home".into()), None).unwrap();
+        assert_eq!(dir, Path::new("/home"));
+    }
+
+    #[test]
+    fn data_dir_flag_overrides_volt_home() {
+        let flag = Path::new("/flag");
+        let dir = pick_volt_dir(Some(flag), Some("/home".into()), None).unwrap();
+        assert_eq!(dir, flag);
+    }
+
+    #[test]
+    fn xdg_data_dir_needs_an_absolute_path() {
+        let home = Some(PathBuf::from("/Users/me"));
+
+        let dir = xdg_data_dir(Some("/data".into()), home.clone());
+        assert_eq!(dir.unwrap(), Path::new("/data"));
+        let dir = xdg_data_dir(Some("relative".into()), home.clone());
+        assert_eq!(dir.unwrap(), Path::new("/Users/me/.local/share"));
+        assert_eq!(xdg_data_dir(None, None), None);
+    }
+}
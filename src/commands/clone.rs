@@ -16,50 +16,191 @@
 
 //! Clone and setup a repository from Github.
 
-use crate::cli::{VoltCommand, VoltConfig};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::{classes::package_manager::PackageManager, utils::errors::VoltError},
+};
 
 use async_trait::async_trait;
 use clap::Parser;
 use colored::Colorize;
-use miette::Result;
-use std::{process, sync::Arc};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use regex::Regex;
+use std::{
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    process,
+};
 
 /// Clone a project and setup a project from a repository
 #[derive(Debug, Parser)]
 pub struct Clone {
-    /// URL of the repository
+    /// URL of the repository, or `user/repo` for a Github repository
     repository: String,
+
+    /// Directory to clone into, defaults to the name of the repository
+    dir: Option<PathBuf>,
+
+    /// Expand `user/repo` shorthand to an SSH URL instead of HTTPS
+    #[clap(long)]
+    ssh: bool,
+
+    /// Number of commits of history to fetch
+    #[clap(long, default_value = "1", conflicts_with = "full")]
+    depth: NonZeroU32,
+
+    /// Fetch the full history instead of a shallow clone
+    #[clap(long)]
+    full: bool,
+
+    /// Show git's verbose output
+    #[clap(short, long)]
+    verbose: bool,
+
+    /// Hide git's progress output
+    #[clap(long)]
+    no_progress: bool,
 }
 
 #[async_trait]
 impl VoltCommand for Clone {
     /// Execute the `volt clone` command
     ///
-    /// Clone and setup a repository from Github
-    /// ## Arguments
-    /// * `app` - Instance of the command (`Arc<App>`)
-    /// ## Examples
-    /// ```
-    /// // Clone the react repository (https://github.com/facebook/react)
-    /// // .exec() is an async call so you need to await it
-    /// Add.exec(app).await;
-    /// ```
-    /// ## Returns
-    /// * `Result<()>`
-    async fn exec(self, _: VoltConfig) -> miette::Result<()> {
-        let exit_code = process::Command::new("cmd")
-            .arg(format!("/C git clone {} --depth=1", self.repository).as_str())
+    /// Clones the repository with git, then installs its dependencies with the package
+    /// manager the project expects.
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let repository = expand_repository(&self.repository, self.ssh);
+
+        let dir = match self.dir {
+            Some(dir) => dir,
+            None => {
+                let dir = clone_dir(&repository);
+                println!("Cloning into {}", dir.display().to_string().bright_cyan());
+                dir
+            }
+        };
+
+        let mut git = process::Command::new("git");
+        git.arg("clone").arg(&repository).arg(&dir);
+
+        if !self.full {
+            git.arg(format!("--depth={}", self.depth));
+        }
+
+        if self.verbose {
+            git.arg("--verbose");
+        }
+
+        if self.no_progress || !config.show_progress() {
+            git.arg("--quiet");
+        }
+
+        let exit_code = git
+            .status()
+            .into_diagnostic()
+            .wrap_err("failed to run `git`, is it installed?")?;
+
+        if !exit_code.success() {
+            return Err(VoltError::GitCloneError { repository }.into());
+        }
+
+        let package_manager = match PackageManager::detect(&dir) {
+            Some(package_manager) => package_manager,
+            None => {
+                println!(
+                    "{} has no package.json, skipping install",
+                    dir.display().to_string().bright_cyan()
+                );
+                return Ok(());
+            }
+        };
+
+        let (program, args) = package_manager.install_command();
+        println!("Installing dependencies with {}", package_manager);
+
+        let exit_code = process::Command::new(program)
+            .args(args)
+            .current_dir(&dir)
             .status()
-            .unwrap();
-
-        if exit_code.success() {
-            process::Command::new("volt")
-                .arg("install")
-                .spawn()
-                .unwrap();
-        } else {
+            .into_diagnostic()
+            .wrap_err(format!("failed to run `{program}`"))?;
+
+        if !exit_code.success() {
+            return Err(VoltError::CloneInstallError {
+                command: [&[program], args].concat().join(" "),
+                dir: dir.display().to_string(),
+            }
+            .into());
         }
 
         Ok(())
     }
 }
+
+/// Expand Github `user/repo` shorthand into a clonable URL, leaving anything else untouched
+fn expand_repository(repository: &str, ssh: bool) -> String {
+    let shorthand = Regex::new(r"^[\w-]+/[\w.-]+$").expect("Valid regex");
+
+    if !shorthand.is_match(repository) || Path::new(repository).exists() {
+        return repository.to_string();
+    }
+
+    let repository = repository.trim_end_matches(".git");
+    if ssh {
+        format!("git@github.com:{repository}.git")
+    } else {
+        format!("https://github.com/{repository}.git")
+    }
+}
+
+/// The directory git clones `repository` into by default
+fn clone_dir(repository: &str) -> PathBuf {
+    let name = repository
+        .trim_end_matches('/')
+        .rsplit(|c| c == '/' || c == ':')
+        .next()
+        .unwrap_or(repository);
+
+    PathBuf::from(name.trim_end_matches(".git"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cloning_a_bogus_repository_fails() {
+        let config = VoltConfig::parse_from(["volt"]);
+        let clone = Clone {
+            repository: "/this/repository/does/not/exist".to_string(),
+            dir: None,
+            depth: NonZeroU32::new(1).unwrap(),
+            full: false,
+            ssh: false,
+            verbose: false,
+            no_progress: false,
+        };
+
+        assert!(clone.exec(config).await.is_err());
+    }
+
+    #[test]
+    fn expands_github_shorthand() {
+        assert_eq!(
+            expand_repository("sshyran/volt", false),
+            "https://github.com/sshyran/volt.git"
+        );
+        assert_eq!(
+            expand_repository("sshyran/volt", true),
+            "git@github.com:sshyran/volt.git"
+        );
+        assert_eq!(
+            expand_repository("https://github.com/sshyran/volt", true),
+            "https://github.com/sshyran/volt"
+        );
+        assert_eq!(
+            expand_repository("git@github.com:sshyran/volt.git", false),
+            "git@github.com:sshyran/volt.git"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+use crate::cli::{VoltCli, VoltCommand, VoltConfig};
+
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+use clap_complete::Shell;
+use miette::Result;
+
+/// Print a shell completion script, e.g. `volt completions zsh > ~/.zfunc/_volt`
+#[derive(Debug, Parser)]
+pub struct Completions {
+    /// Shell to generate completions for
+    #[clap(arg_enum)]
+    shell: Shell,
+}
+
+#[async_trait]
+impl VoltCommand for Completions {
+    /// Execute the `volt completions` command
+    ///
+    /// Writes the completion script for every volt subcommand to stdout.
+    async fn exec(self, _config: VoltConfig) -> Result<()> {
+        let mut cmd = VoltCli::command();
+        let name = cmd.get_name().to_string();
+
+        clap_complete::generate(self.shell, &mut cmd, name, &mut std::io::stdout());
+
+        Ok(())
+    }
+}
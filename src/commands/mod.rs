@@ -18,6 +18,7 @@ pub mod audit;
 pub mod check;
 pub mod clean;
 pub mod clone;
+pub mod completions;
 pub mod create;
 pub mod deploy;
 pub mod discord;
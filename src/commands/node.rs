@@ -22,31 +22,95 @@ use std::{
     fmt::{format, Display},
     fs::{DirEntry, File},
     io::{BufReader, Write},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     process::Command,
     str, string,
+    sync::Arc,
     thread::current,
     time::Duration,
 };
 
 use async_trait::async_trait;
 use base64::decode;
-use clap::CommandFactory;
-use clap::{ArgMatches, ErrorKind, Parser, Subcommand};
+use clap::{ArgEnum, ArgMatches, Parser, Subcommand};
 use colored::Colorize;
 use futures::{
     future::{lazy, Future},
     io,
     stream::FuturesOrdered,
 };
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use miette::Result;
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use miette::{IntoDiagnostic, Result, WrapErr};
 use node_semver::{Range, Version};
-use serde::{Deserialize, Deserializer};
-use tempfile::tempdir;
-use tokio::fs;
+use serde::{Deserialize, Deserializer, Serialize};
+use tokio::{fs, sync::Semaphore};
+
+pub(crate) mod alias;
+mod cancel;
+pub(crate) mod checksum;
+mod current;
+mod doctor;
+pub(crate) mod download;
+mod exec;
+pub(crate) mod extract;
+mod index;
+mod latest;
+mod local;
+pub(crate) mod lock;
+pub(crate) mod meta;
+mod migrate;
+pub(crate) mod mirror;
+mod pick;
+mod pin;
+mod prune;
+mod registry;
+mod report;
+mod shell_env;
+mod shell_init;
+mod signature;
+mod verify;
+mod version_file;
+mod which;
+#[cfg(windows)]
+pub(crate) mod windows_path;
+
+pub use alias::NodeAlias;
+pub use current::NodeCurrent;
+pub use doctor::NodeDoctor;
+pub use exec::NodeExec;
+pub use latest::{NodeLatest, NodeLatestLts};
+pub use migrate::NodeMigrate;
+pub use pin::NodePin;
+pub use prune::NodePrune;
+pub use shell_env::NodeEnv;
+pub use shell_init::{NodeShellInit, Shell};
+pub use verify::NodeVerify;
+pub use which::NodeWhich;
 
-use crate::cli::{VoltCommand, VoltConfig};
+#[cfg(unix)]
+use crate::node::remove_link;
+#[cfg(windows)]
+use crate::node::windows_shims;
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::{prompt::prompts::Confirm, utils::errors::VoltError},
+    node::{
+        check_builds, latest_lts, needs_64_bit, partial_range, resolve_in, select, strip_v,
+        unalias, Release, Releases,
+    },
+};
+
+/// `println!`, unless `--porcelain` reserves stdout for the result and it goes to stderr
+macro_rules! status {
+    ($porcelain:expr, $($arg:tt)*) => {
+        if $porcelain {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
 
 const PLATFORM: Os = if cfg!(target_os = "windows") {
     Os::Windows
@@ -58,14 +122,111 @@ const PLATFORM: Os = if cfg!(target_os = "windows") {
     Os::Unknown
 };
 
-const ARCH: Arch = if cfg!(target_arch = "X86") {
+const ARCH: Arch = if cfg!(target_arch = "x86") {
     Arch::X86
 } else if cfg!(target_arch = "x86_64") {
     Arch::X64
+} else if cfg!(target_arch = "aarch64") {
+    Arch::Arm64
+} else if cfg!(target_arch = "arm") {
+    Arch::Armv7l
 } else {
     Arch::Unknown
 };
 
+/// Extensions of the release artifacts for this platform, the first one is downloaded by default
+const ARTIFACT_EXTS: &[&str] = if cfg!(target_os = "windows") {
+    &["zip"]
+} else {
+    &["tar.xz", "tar.gz"]
+};
+
+/// Cross-installs for other platforms live in `<node dir>/cross/<platform>-<arch>`
+const CROSS_DIR: &str = "cross";
+
+/// Platform and architecture a release is built for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Target {
+    os: Os,
+    arch: Arch,
+}
+
+impl Target {
+    /// The platform volt is running on
+    pub(crate) const NATIVE: Target = Target {
+        os: PLATFORM,
+        arch: ARCH,
+    };
+
+    pub(crate) fn is_native(&self) -> bool {
+        *self == Self::NATIVE
+    }
+
+    /// Extension of the release artifact for this target, windows releases are always zips
+    fn ext(&self, compression: Compression) -> &'static str {
+        match (self.os, compression) {
+            (Os::Windows, _) => "zip",
+            (_, Compression::Xz) => "tar.xz",
+            (_, Compression::Gz) => "tar.gz",
+        }
+    }
+
+    /// Name of the release artifact of `version` for this target
+    pub(crate) fn artifact(&self, version: &Version, compression: Compression) -> String {
+        format!("node-v{version}-{self}.{}", self.ext(compression))
+    }
+
+    /// Name of this target's artifact in the `files` listed for each release in `index.json`
+    ///
+    /// The `.tar.xz` and `.tar.gz` of a release share an entry.
+    pub(crate) fn key(&self) -> String {
+        match self.os {
+            Os::Windows => format!("win-{}-zip", self.arch),
+            Os::Macos => format!("osx-{}-tar", self.arch),
+            _ => self.to_string(),
+        }
+    }
+
+    /// The node binary in a version directory of this target
+    pub(crate) fn node_binary(&self, version_dir: &Path) -> PathBuf {
+        match self.os {
+            Os::Windows => version_dir.join("node.exe"),
+            _ => version_dir.join("bin").join("node"),
+        }
+    }
+
+    /// Directory versions built for this target are installed into
+    pub(crate) fn install_dir(&self, node_dir: &Path) -> PathBuf {
+        if self.is_native() {
+            node_dir.to_path_buf()
+        } else {
+            node_dir.join(CROSS_DIR).join(self.to_string())
+        }
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.os, self.arch)
+    }
+}
+
+/// Compression of the tarballs downloaded for unix releases
+#[derive(Debug, Clone, Copy, PartialEq, ArgEnum)]
+pub(crate) enum Compression {
+    Xz,
+    Gz,
+}
+
+impl Compression {
+    /// xz is smaller, but there's no xz decoder on windows
+    pub(crate) const DEFAULT: Compression = if cfg!(target_os = "windows") {
+        Compression::Gz
+    } else {
+        Compression::Xz
+    };
+}
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 enum Lts {
@@ -94,14 +255,20 @@ pub struct NodeVersion {
     pub version: Version,
     #[serde(deserialize_with = "deserialize")]
     pub lts: Option<String>,
+    /// Release date, `YYYY-MM-DD`
+    #[serde(default)]
+    pub date: Option<String>,
     pub files: Vec<String>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, ArgEnum)]
 enum Os {
+    #[clap(name = "win", alias = "windows")]
     Windows,
+    #[clap(name = "darwin", alias = "macos")]
     Macos,
     Linux,
+    #[clap(skip)]
     Unknown,
 }
 impl Display for Os {
@@ -116,10 +283,13 @@ impl Display for Os {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, ArgEnum)]
 enum Arch {
     X86,
     X64,
+    Arm64,
+    Armv7l,
+    #[clap(skip)]
     Unknown,
 }
 
@@ -128,27 +298,65 @@ impl Display for Arch {
         let s = match *self {
             Arch::X86 => "x86",
             Arch::X64 => "x64",
+            Arch::Arm64 => "arm64",
+            Arch::Armv7l => "armv7l",
             _ => unreachable!(),
         };
         write!(f, "{}", s)
     }
 }
 
+/// List the released versions on the configured mirror, using the cached index when fresh
+pub(crate) async fn fetch_node_versions(
+    config: &VoltConfig,
+    client: &reqwest::Client,
+) -> Result<Vec<NodeVersion>> {
+    index::node_versions(client, config).await
+}
+
 /// Manage node versions
+///
+/// Without a subcommand, shows the version in use and how many are installed.
 #[derive(Debug, Parser)]
 pub struct Node {
     #[clap(subcommand)]
-    cmd: NodeCommand,
+    cmd: Option<NodeCommand>,
 }
 
 #[async_trait]
 impl VoltCommand for Node {
     async fn exec(self, config: VoltConfig) -> Result<()> {
+        // Held until the command is done, `install --default` runs `use` under the same lock
+        let _lock = if self
+            .cmd
+            .as_ref()
+            .map_or(false, NodeCommand::changes_data_dir)
+        {
+            Some(lock::acquire(&config).await?)
+        } else {
+            None
+        };
+
         match self.cmd {
-            NodeCommand::Use(x) => x.exec(config).await,
-            NodeCommand::Install(x) => x.exec(config).await,
-            NodeCommand::Remove(x) => x.exec(config).await,
-            NodeCommand::List(x) => x.exec(config).await,
+            Some(NodeCommand::Use(x)) => x.exec(config).await,
+            Some(NodeCommand::Install(x)) => x.exec(config).await,
+            Some(NodeCommand::Reinstall(x)) => x.exec(config).await,
+            Some(NodeCommand::Remove(x)) => x.exec(config).await,
+            Some(NodeCommand::Prune(x)) => x.exec(config).await,
+            Some(NodeCommand::List(x)) => x.exec(config).await,
+            Some(NodeCommand::Current(x)) => x.exec(config).await,
+            Some(NodeCommand::Exec(x)) => x.exec(config).await,
+            Some(NodeCommand::Alias(x)) => x.exec(config).await,
+            Some(NodeCommand::ShellInit(x)) => x.exec(config).await,
+            Some(NodeCommand::Which(x)) => x.exec(config).await,
+            Some(NodeCommand::Verify(x)) => x.exec(config).await,
+            Some(NodeCommand::Latest(x)) => x.exec(config).await,
+            Some(NodeCommand::LatestLts(x)) => x.exec(config).await,
+            Some(NodeCommand::Doctor(x)) => x.exec(config).await,
+            Some(NodeCommand::Pin(x)) => x.exec(config).await,
+            Some(NodeCommand::Env(x)) => x.exec(config).await,
+            Some(NodeCommand::Migrate(x)) => x.exec(config).await,
+            None => summary(&config),
         }
     }
 }
@@ -157,47 +365,270 @@ impl VoltCommand for Node {
 pub enum NodeCommand {
     Use(NodeUse),
     Install(NodeInstall),
+    Reinstall(NodeReinstall),
     Remove(NodeRemove),
+    Prune(NodePrune),
     List(NodeList),
+    Current(NodeCurrent),
+    Exec(NodeExec),
+    Alias(NodeAlias),
+    ShellInit(NodeShellInit),
+    Which(NodeWhich),
+    Verify(NodeVerify),
+    Latest(NodeLatest),
+    LatestLts(NodeLatestLts),
+    Doctor(NodeDoctor),
+    Pin(NodePin),
+    Env(NodeEnv),
+    Migrate(NodeMigrate),
+}
+
+impl Node {
+    /// Whether only errors should be printed
+    pub fn is_silent(&self) -> bool {
+        matches!(&self.cmd, Some(NodeCommand::Use(x)) if x.silent)
+    }
 }
+
+/// Print the version in use and how many are installed, for a bare `volt node`
+fn summary(config: &VoltConfig) -> Result<()> {
+    let installed = installed_versions(config)?;
+
+    match current_version(config)? {
+        Some(version) => println!("Using node {}", version.truecolor(0, 255, 0)),
+        None => println!("No node version in use"),
+    }
+
+    match installed.len() {
+        0 => println!("No versions installed, run `volt node install <version>` to install one"),
+        1 => println!("1 version installed"),
+        n => println!("{n} versions installed"),
+    }
+
+    println!(
+        "\nRun `volt node --help` to see every command, e.g. `volt node list` or `volt node use`"
+    );
+
+    Ok(())
+}
+
+impl NodeCommand {
+    /// Whether the command installs, removes or switches versions, read-only ones skip the lock
+    fn changes_data_dir(&self) -> bool {
+        matches!(
+            self,
+            Self::Use(_)
+                | Self::Install(_)
+                | Self::Reinstall(_)
+                | Self::Remove(_)
+                | Self::Prune(_)
+                | Self::Migrate(_)
+        )
+    }
+}
+
 /// List available NodeJS versions
 #[derive(Debug, Parser)]
-pub struct NodeList {}
+pub struct NodeList {
+    /// List versions available on the mirror instead of installed ones
+    #[clap(long)]
+    remote: bool,
+
+    /// Only list LTS releases
+    #[clap(long, requires = "remote")]
+    lts: bool,
+
+    /// Only list releases of this major version, e.g. `18`
+    #[clap(long, requires = "remote")]
+    major: Option<u64>,
+
+    /// Only list the newest N releases
+    #[clap(long, requires = "remote")]
+    limit: Option<usize>,
+
+    /// Show how much disk space each installed version uses
+    #[clap(long, conflicts_with = "remote")]
+    sizes: bool,
+
+    /// Don't mark the active version, one version per line
+    #[clap(long)]
+    plain: bool,
+
+    /// Print the versions as a JSON array, with `--remote` too
+    #[clap(long, conflicts_with = "sizes")]
+    json: bool,
+
+    /// List installed versions newest first
+    #[clap(long, conflicts_with_all = &["remote", "sizes", "json"])]
+    desc: bool,
+}
+
+/// An installed version, as printed by `volt node list --json`
+#[derive(Serialize)]
+struct ListEntry {
+    version: String,
+    current: bool,
+    lts: Option<String>,
+    /// Where and when the version was installed, if volt recorded it
+    install: Option<meta::InstallMeta>,
+}
+
+/// A release on the mirror, as printed by `volt node list --remote --json`
+#[derive(Serialize)]
+struct RemoteEntry {
+    version: String,
+    lts: Option<String>,
+    date: Option<String>,
+    /// Builds of the release, named like `linux-x64` or `win-x64-zip`
+    files: Vec<String>,
+    installed: bool,
+}
+
+impl NodeList {
+    /// Print every installed version as JSON, newest first
+    async fn list_json(&self, config: &VoltConfig) -> Result<()> {
+        let mut installed = crate::node::installed(config)?;
+        installed.reverse();
+
+        // LTS codenames are a nice to have, don't fail without a mirror or cache
+        let index = match config.http_client() {
+            Ok(client) => fetch_node_versions(config, &client)
+                .await
+                .unwrap_or_default(),
+            Err(_) => vec![],
+        };
+
+        let entries = installed
+            .into_iter()
+            .map(|installed| {
+                let lts = index
+                    .iter()
+                    .find(|n| n.version == installed.version)
+                    .and_then(|n| n.lts.clone());
+
+                ListEntry {
+                    version: installed.version.to_string(),
+                    current: installed.current,
+                    install: meta::read(&installed.path),
+                    lts,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&entries).into_diagnostic()?
+        );
+
+        Ok(())
+    }
+
+    /// Print every installed version with its size, newest first
+    fn list_sizes(&self, config: &VoltConfig) -> Result<()> {
+        let mut installed = crate::node::installed(config)?;
+        installed.reverse();
+
+        let mut total = 0;
+        for installed in installed {
+            let size = dir_size(&installed.path);
+            total += size;
+
+            println!("{:10} {}", installed.version.to_string(), HumanBytes(size));
+        }
+
+        println!(
+            "{:10} {}",
+            "total",
+            HumanBytes(total).to_string().bright_green()
+        );
+
+        Ok(())
+    }
+
+    /// Print every version on the mirror, newest first
+    async fn list_remote(&self, config: &VoltConfig) -> Result<()> {
+        let mut node_versions = fetch_node_versions(config, &config.http_client()?).await?;
+        node_versions.sort_by(|a, b| b.version.cmp(&a.version));
+
+        let installed = installed_versions(config)?;
+
+        let node_versions = node_versions
+            .into_iter()
+            .filter(|n| !self.lts || n.lts.is_some())
+            .filter(|n| self.major.map_or(true, |major| n.version.major == major))
+            .take(self.limit.unwrap_or(usize::MAX));
+
+        if self.json {
+            let entries = node_versions
+                .map(|n| RemoteEntry {
+                    installed: installed.contains(&n.version),
+                    version: n.version.to_string(),
+                    lts: n.lts,
+                    date: n.date,
+                    files: n.files,
+                })
+                .collect::<Vec<_>>();
+
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&entries).into_diagnostic()?
+            );
+            return Ok(());
+        }
+
+        for n in node_versions {
+            let lts = n
+                .lts
+                .as_ref()
+                .map(|codename| format!("LTS: {codename}"))
+                .unwrap_or_default();
+
+            let version = n.version.to_string();
+            if installed.contains(&n.version) {
+                println!("{:10} {:16} installed", version.truecolor(0, 255, 0), lts);
+            } else {
+                println!("{:10} {}", version, lts);
+            }
+        }
+
+        Ok(())
+    }
+}
 
 #[async_trait]
 impl VoltCommand for NodeList {
     // On windows, versions install to C:\Users\[name]\AppData\Roaming\volt\node\[version]
     async fn exec(self, config: VoltConfig) -> Result<()> {
-        let node_path = {
-            let datadir = dirs::data_dir().unwrap().join("volt").join("node");
-            if !datadir.exists() {
-                eprintln!("No NodeJS versions installed!");
-                std::process::exit(1);
-            };
-            datadir
-        };
+        if self.remote {
+            return self.list_remote(&config).await;
+        }
 
-        let files = std::fs::read_dir(&node_path)
-            .unwrap()
-            .map(|d| {
-                d.unwrap()
-                    .path()
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_owned()
-            })
-            .filter(|f| f != "current")
-            .collect::<Vec<String>>();
+        if self.sizes {
+            return self.list_sizes(&config);
+        }
+
+        if self.json {
+            return self.list_json(&config).await;
+        }
 
-        if files.is_empty() {
-            eprintln!("No NodeJS versions installed!");
-            std::process::exit(1);
+        let mut versions = crate::node::installed(&config)?;
+        if versions.is_empty() {
+            return Err(VoltError::NodeNoVersionsInstalled.into());
         }
 
-        for file in files {
-            println!("{file}");
+        if self.desc {
+            versions.reverse();
+        }
+
+        for installed in versions {
+            let version = installed.version.to_string();
+            if self.plain {
+                println!("{version}");
+            } else if installed.current {
+                println!("* {}", version.truecolor(0, 255, 0));
+            } else {
+                println!("  {version}");
+            }
         }
 
         Ok(())
@@ -207,470 +638,1417 @@ impl VoltCommand for NodeList {
 /// Switch current node version
 #[derive(Debug, Parser)]
 pub struct NodeUse {
-    /// Version to use
-    version: String,
+    /// Version to use, read from the closest `.nvmrc` or `.node-version` if omitted
+    ///
+    /// An exact version is used as it is, a partial one like `18` picks the newest installed
+    /// match and anything else is looked up as an alias.
+    version: Option<String>,
+
+    /// Shell to print instructions for, detected from `SHELL` if omitted
+    #[clap(long, arg_enum)]
+    shell: Option<Shell>,
+
+    /// Replace files in the link directory even if volt didn't create them
+    #[clap(long)]
+    force: bool,
+
+    /// Run the version's `corepack enable`, so `pnpm` and `yarn` use it too
+    #[clap(long)]
+    enable_corepack: bool,
+
+    /// Point the version's bundled npm at this registry, instead of `--npm-registry`
+    #[clap(long, value_name = "URL")]
+    registry: Option<String>,
+
+    /// Only print errors, e.g. when switching from a prompt or `cd` hook
+    #[clap(long, conflicts_with = "verbose")]
+    silent: bool,
+
+    /// Print every binary that's linked
+    #[clap(long)]
+    verbose: bool,
+
+    /// Only print the version switched to on stdout, everything else goes to stderr
+    #[clap(long)]
+    porcelain: bool,
 }
 
 #[async_trait]
 impl VoltCommand for NodeUse {
     async fn exec(self, config: VoltConfig) -> Result<()> {
-        #[cfg(target_family = "windows")]
-        {
-            use_windows(self.version).await;
-        }
+        let porcelain = self.porcelain;
+        let version = self.run(config).await?;
 
-        #[cfg(target_family = "unix")]
-        {
-            // FIXME: This is just to meet a spec to get a grade in a class
-            // will remove after class is over
-            {
-                if std::fs::read_dir(get_node_dir())
-                    .unwrap()
-                    .map(|f| f.unwrap())
-                    .next()
-                    .is_none()
-                {
-                    eprintln!("No node versions installed!");
-                    std::process::exit(1);
-                }
-            }
+        if porcelain {
+            println!("{version}");
+        }
 
-            let node_path = get_node_dir().join(&self.version);
+        Ok(())
+    }
+}
 
-            if node_path.exists() {
-                let link_dir = dirs::home_dir().unwrap().join(".local").join("bin");
+impl NodeUse {
+    /// Switch versions, returning the version switched to
+    async fn run(self, config: VoltConfig) -> Result<String> {
+        let porcelain = self.porcelain;
+        let version = match self.version {
+            Some(version) => version,
+            None => version_file::read(&config.cwd()?)?,
+        };
+        if installed_versions(&config)?.is_empty() {
+            return Err(VoltError::NodeNoVersionsInstalled.into());
+        }
+        let version = resolve_installed(&version, &config).await?;
 
-                let to_install = node_path.join("bin");
-                let current = node_path.parent().unwrap().join("current");
+        let registry = self.registry.clone().or_else(|| config.npm_registry());
+        if let Some(registry) = &registry {
+            registry::validate(registry)?;
+        }
 
-                // TODO: Handle file deletion errors
-                if current.exists() {
-                    // Remove all the currently installed links
-                    for f in std::fs::read_dir(&current).unwrap() {
-                        let original = f.unwrap().file_name();
-                        let installed = link_dir.join(&original);
-                        if installed.exists() {
-                            std::fs::remove_file(installed).unwrap();
-                        }
-                    }
+        let switched = select(&config, &version, self.force)?;
 
-                    // Remove the old link
-                    std::fs::remove_file(&current).unwrap();
+        if self.verbose {
+            for (link, original) in &switched.linked {
+                status!(porcelain, "Linking to {:?} from {:?}", link, original);
+            }
+        }
 
-                    // Make a new one to the currently installed version
-                    std::os::unix::fs::symlink(&to_install, current).unwrap();
-                } else {
-                    println!("Installing first version");
-                    std::os::unix::fs::symlink(&to_install, current).unwrap();
-                }
+        if !self.silent {
+            let first = if switched.first {
+                " (first version)"
+            } else {
+                ""
+            };
+            status!(
+                porcelain,
+                "Using node {version}{first}, linked {} binaries",
+                switched.linked.len()
+            );
+        }
 
-                for f in std::fs::read_dir(&to_install).unwrap() {
-                    let original = f.unwrap().path();
-                    let fname = original.file_name().unwrap();
-                    let link = link_dir.join(fname);
+        if !switched.failed.is_empty() {
+            eprintln!("{} some binaries couldn't be linked:", "warning:".yellow());
+            for (link, e) in &switched.failed {
+                eprintln!("  {}: {}", link.display(), e);
+            }
+        }
 
-                    // INFO: DOC: Need to run `rehash` in zsh for the changes to take effect
-                    println!("Linking to {:?} from {:?}", link, original);
+        if switched.path_updated {
+            status!(
+                porcelain,
+                "PATH environment variable updated.\nYou will need to restart your terminal for changes to apply."
+            );
+        }
 
-                    // TODO: Do something with this error
-                    let _ = std::fs::remove_file(&link);
+        // INFO: zsh and bash cache command paths, `volt node shell-init` prints a wrapper that
+        // rehashes after switching
+        #[cfg(unix)]
+        if !self.silent {
+            let shell = self.shell.or_else(Shell::detect);
+            print_use_hints(shell, &config.bin_dir()?, porcelain);
+        }
 
-                    // maybe ship `vnm` as a shell function to run `volt node use ... && rehash` on
-                    // zsh?
-                    let _symlink = std::os::unix::fs::symlink(original, link).unwrap();
+        if let Some(registry) = registry {
+            if registry::set(&config.node_dir()?.join(&version), &registry)? {
+                if !self.silent {
+                    status!(porcelain, "Pointed npm at {registry}");
                 }
             } else {
-                println!("That version of node is not installed!\nTry \"volt node install {}\" to install that version.", self.version)
+                eprintln!(
+                    "{} node {version} doesn't include npm, not setting its registry",
+                    "warning:".yellow()
+                );
             }
         }
-        Ok(())
+
+        if self.enable_corepack {
+            enable_corepack(&config, &version)?;
+        }
+
+        Ok(version)
+    }
+}
+
+/// Install corepack's `pnpm` and `yarn` shims for `version` into the link directory
+///
+/// Versions from before corepack was bundled (node 14.19 / 16.9) only get a warning.
+fn enable_corepack(config: &VoltConfig, version: &str) -> Result<()> {
+    let name = if cfg!(windows) {
+        "corepack.cmd"
+    } else {
+        "corepack"
+    };
+    let corepack = get_bin_dir(config, version)?.join(name);
+
+    if !corepack.exists() {
+        eprintln!(
+            "{} node {version} doesn't include corepack, skipping `corepack enable`",
+            "warning:".yellow()
+        );
+        return Ok(());
+    }
+
+    let status = Command::new(&corepack)
+        .arg("enable")
+        .arg("--install-directory")
+        .arg(config.bin_dir()?)
+        .status()
+        .into_diagnostic()
+        .wrap_err(format!("failed to run {}", corepack.display()))?;
+
+    if !status.success() {
+        return Err(VoltError::NodeCorepackFailed {
+            status: status.to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Whether `dir` is one of the entries of `PATH`
+fn is_on_path(dir: &Path) -> bool {
+    env::var_os("PATH")
+        .map(|path| env::split_paths(&path).any(|p| p == dir))
+        .unwrap_or(false)
+}
+
+/// Command that adds `dir` to `PATH` in `shell`
+#[cfg(unix)]
+fn path_fix(shell: Option<Shell>, dir: &Path) -> String {
+    match shell {
+        Some(Shell::Fish) => format!("fish_add_path {}", dir.display()),
+        _ => format!("export PATH=\"{}:$PATH\"", dir.display()),
+    }
+}
+
+/// Tell the user how to pick up the newly linked binaries in `link_dir`
+#[cfg(unix)]
+fn print_use_hints(shell: Option<Shell>, link_dir: &Path, porcelain: bool) {
+    if !is_on_path(link_dir) {
+        eprintln!(
+            "{} {} is not on your PATH, add it with `{}`",
+            "warning:".yellow(),
+            link_dir.display(),
+            path_fix(shell, link_dir)
+        );
     }
+
+    // fish looks commands up on every run, bash and zsh cache them
+    let rehash = match shell {
+        Some(Shell::Zsh) => "rehash",
+        Some(Shell::Bash) => "hash -r",
+        Some(Shell::Fish) | None => return,
+    };
+
+    status!(
+        porcelain,
+        "Run `{}` to use the new version in this shell, or see `volt node shell-init`",
+        rehash
+    );
 }
 
 /// Install one or more versions of node
+///
+/// Versions may be exact (`18.19.0`), partial (`18` or `18.17`, the newest release in that
+/// line) or any other range (`'>=18 <20'`).
 #[derive(Debug, Parser)]
 pub struct NodeInstall {
-    /// Versions to install
+    /// Versions to install, or paths to release tarballs, nothing is installed if any of them
+    /// can't be found
+    #[clap(required_unless_present_any = &["lts", "interactive"])]
     versions: Vec<String>,
+
+    /// Pick the versions to install from a list of the mirror's releases
+    #[clap(short, long, conflicts_with_all = &["versions", "lts"])]
+    interactive: bool,
+
+    /// Install the latest LTS release, optionally restricted to a codename (e.g. `hydrogen`)
+    #[clap(long)]
+    lts: Option<Option<String>>,
+
+    /// Skip verifying downloads against the release's `SHASUMS256.txt`
+    #[clap(long)]
+    skip_checksum: bool,
+
+    /// Maximum number of versions to download at once
+    #[clap(short, long, default_value = "3")]
+    jobs: NonZeroUsize,
+
+    /// Switch to the installed version afterwards, the newest one if several are given
+    #[clap(long, alias = "use")]
+    default: bool,
+
+    /// Install the build for another platform, e.g. to stage windows releases from linux
+    ///
+    /// Cross-installs are kept apart from native ones, in `<node dir>/cross/<platform>-<arch>`,
+    /// and can't be selected with `volt node use`.
+    #[clap(long, arg_enum, conflicts_with = "default")]
+    platform: Option<Os>,
+
+    /// Install the build for another CPU architecture, e.g. arm64 on an x64 host
+    ///
+    /// Like `--platform`, this is a cross-install that can't be selected with `volt node use`.
+    #[clap(long, arg_enum, conflicts_with = "default")]
+    arch: Option<Arch>,
+
+    /// Skip ranges an installed version already satisfies, instead of installing the newest
+    /// match. Exact versions are always installed.
+    #[clap(long)]
+    prefer_installed: bool,
+
+    /// Compression of the tarball to download, `gz` for systems that can't decode xz
+    ///
+    /// When a `.tar.xz` fails to unpack, the `.tar.gz` is tried instead. Windows releases
+    /// are always zips.
+    #[clap(long, arg_enum)]
+    compression: Option<Compression>,
+
+    /// Only print the installed versions on stdout, one per line, everything else goes to
+    /// stderr
+    #[clap(long)]
+    porcelain: bool,
+
+    /// Download and verify the release tarballs into a directory instead of installing them
+    ///
+    /// The saved tarballs can be installed later with `volt node install <path>`, without
+    /// reaching the mirror.
+    #[clap(long, value_name = "DIR", conflicts_with_all = &["default", "prefer-installed"])]
+    download_only: Option<PathBuf>,
+
+    /// Resolve the versions and print what would be downloaded and where it would be
+    /// installed, without downloading or installing anything
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Download versions even if they're already installed, set by `volt node reinstall`
+    #[clap(skip)]
+    force: bool,
+}
+
+/// Download installed versions of node again, replacing the existing install
+#[derive(Debug, Parser)]
+pub struct NodeReinstall {
+    #[clap(flatten)]
+    install: NodeInstall,
 }
 
 #[async_trait]
-impl VoltCommand for NodeInstall {
-    // 32bit macos/linux systems cannot download a version of node >= 10.0.0
-    // They stopped making 32bit builds after that version
-    // https://nodejs.org/dist/
-    // TODO: Handle errors with file already existing and handle file creation/deletion errors
-    // TODO: Only make a tempdir if we have versions to download, i.e. verify all versions before
-    //       creating the directory
-    async fn exec(self, _: VoltConfig) -> Result<()> {
-        if self.versions.is_empty() {
-            let mut cmd = NodeInstall::command();
-            cmd.error(
-                ErrorKind::ArgumentConflict,
-                "Must have at least one version",
-            )
-            .exit();
+impl VoltCommand for NodeReinstall {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        // Versions are swapped in place, so `current` and the linked binaries keep working
+        NodeInstall {
+            force: true,
+            ..self.install
         }
+        .exec(config)
+        .await
+    }
+}
 
-        tracing::debug!("On platform '{}' and arch '{}'", PLATFORM, ARCH);
-        let dir = tempdir().unwrap();
-        tracing::debug!("Temp dir is {:?}", dir);
+/// What the requested versions resolved to, before anything is downloaded
+#[derive(Default)]
+struct Resolved {
+    /// Releases to download
+    versions: Vec<Version>,
+    /// What each of `versions` was asked for as, for the summary table
+    requested_as: Vec<String>,
+    /// Installed versions that satisfy a requested range, with `--prefer-installed`
+    satisfied: Vec<Version>,
+    /// Summary table rows for the satisfied ranges
+    rows: Vec<report::Row>,
+}
 
-        let mirror = "https://nodejs.org/dist";
+impl NodeInstall {
+    /// Print what the install would do, once every version is resolved
+    ///
+    /// Artifacts come from the primary mirror here, the real install may fall back to another.
+    fn print_plan(
+        &self,
+        config: &VoltConfig,
+        target: Target,
+        versions: &[Version],
+        local_artifacts: &[local::LocalArtifact],
+        satisfied: &[Version],
+    ) -> Result<()> {
+        let node_dir = config.node_dir()?;
+        let install_dir = target.install_dir(&node_dir);
+        let mirror = config.node_mirror()?;
+        let compression = self.compression.unwrap_or(Compression::DEFAULT);
+        let is_installed = |dest: &Path| !self.force && dest.exists();
+
+        for artifact in local_artifacts {
+            let dest = node_dir.join(artifact.version.to_string());
+            if self.download_only.is_some() {
+                println!("{} is already on disk", artifact.path.display());
+            } else if is_installed(&dest) {
+                println!("node {} is already installed", artifact.version);
+            } else {
+                println!(
+                    "Would install {} to {}",
+                    artifact.path.display(),
+                    dest.display()
+                );
+            }
+        }
 
-        let node_versions: Vec<NodeVersion> = reqwest::get(format!("{}/index.json", mirror))
-            .await
-            .unwrap()
-            .json()
-            .await
-            .unwrap();
+        for version in versions {
+            let url = format!(
+                "{mirror}/v{version}/{}",
+                target.artifact(version, compression)
+            );
+            let dest = install_dir.join(version.to_string());
+
+            match &self.download_only {
+                Some(dir) => println!("Would download {url} to {}", dir.display()),
+                None if is_installed(&dest) => println!("node {version} is already installed"),
+                None => println!("Would download {url} and install it to {}", dest.display()),
+            }
+        }
 
-        let node_path = {
-            let datadir = dirs::data_dir().unwrap().join("volt").join("node");
-            if !datadir.exists() {
-                std::fs::create_dir_all(&datadir).unwrap();
+        if self.default {
+            let newest = versions
+                .iter()
+                .chain(local_artifacts.iter().map(|artifact| &artifact.version))
+                .chain(satisfied)
+                .max();
+            if let Some(newest) = newest {
+                println!("Would switch to node {newest}");
             }
-            datadir
+        }
+
+        Ok(())
+    }
+
+    /// Resolve every requested version before downloading, any version that can't be found
+    /// aborts the whole install so a typo never leaves only some of them installed
+    fn resolve(
+        &self,
+        config: &VoltConfig,
+        releases: &Releases,
+        target: Target,
+        remote: &[&str],
+    ) -> Result<Resolved> {
+        let porcelain = self.porcelain;
+        let mut resolved = Resolved::default();
+
+        let installed = if self.prefer_installed {
+            versions_in(&target.install_dir(&config.node_dir()?))
+        } else {
+            vec![]
         };
 
-        let mut validversions = vec![];
-        let mut download_url = format!("{}/", mirror);
+        if let Some(codename) = &self.lts {
+            let requested = match codename {
+                Some(codename) => format!("lts/{codename}"),
+                None => "lts".to_string(),
+            };
+            resolved
+                .versions
+                .push(resolve_in(config, releases, &requested)?);
+            resolved.requested_as.push(requested);
+        }
 
-        for v in &self.versions {
-            let current_version: Option<Version> = if let Ok(ver) = v.parse() {
-                if cfg!(all(unix, target_arch = "X86")) && ver >= Version::parse("10.0.0").unwrap()
-                {
-                    println!("32 bit versions are not available for MacOS and Linux after version 10.0.0!");
+        for v in remote {
+            let v = strip_v(v);
+            // 32bit macos/linux systems cannot download a version of node >= 10.0.0, they
+            // stopped making 32bit builds after that version: https://nodejs.org/dist/
+            if cfg!(all(unix, target_arch = "x86")) && needs_64_bit(v) {
+                status!(
+                    porcelain,
+                    "32 bit versions are not available for macos and linux after version 10.0.0!"
+                );
+                continue;
+            }
+
+            if let Ok(range) = v.parse::<Range>() {
+                let newest = installed.iter().rev().find(|i| i.satisfies(&range));
+                if let (Some(newest), Err(_)) = (newest, v.parse::<Version>()) {
+                    status!(porcelain, "`{v}` is already satisfied by {newest}");
+                    resolved.satisfied.push(newest.clone());
+                    resolved.rows.push(report::Row {
+                        requested: v.to_string(),
+                        version: newest.to_string(),
+                        status: report::Status::AlreadyInstalled,
+                        size: None,
+                    });
                     continue;
                 }
+            }
 
-                // TODO: Maybe suggest the closest available version if not found?
+            let version = resolve_in(config, releases, v)?;
+            if v.parse::<Version>().is_err() {
+                status!(
+                    porcelain,
+                    "`{}` → installing {}",
+                    v,
+                    version.to_string().truecolor(0, 255, 0)
+                );
+            }
 
-                let mut found = false;
-                for n in &node_versions {
-                    if *v == n.version.to_string() {
-                        tracing::debug!("found version '{}' with URL '{}'", v, download_url);
-                        found = true;
-                        break;
-                    }
-                }
+            // Two requests for the same release, e.g. `18` and `lts`, would unpack it twice
+            if resolved.versions.contains(&version) {
+                continue;
+            }
 
-                if found {
-                    Some(ver)
-                } else {
-                    None
-                }
-            } else if let Ok(ver) = v.parse::<Range>() {
-                //volt install ^12
-                let max_ver = node_versions
-                    .iter()
-                    .filter(|x| x.version.satisfies(&ver))
-                    .map(|v| v.version.clone())
-                    .max();
-
-                if cfg!(all(unix, target_arch = "X86"))
-                    && Range::parse(">=10").unwrap().allows_any(&ver)
-                {
-                    println!("32 bit versions are not available for macos and linux after version 10.0.0!");
-                    continue;
-                }
+            resolved.versions.push(version);
+            resolved.requested_as.push(v.to_string());
+        }
 
-                max_ver
-            } else {
-                // TODO: Not a valid version
-                println!("Invalid version: {}!", v.truecolor(255, 0, 0));
-                std::process::exit(1);
-            };
+        Ok(resolved)
+    }
 
-            if let Some(version) = current_version {
-                validversions.push(version)
-            } else {
-                println!("Invalid version: {}!", v.truecolor(255, 0, 0));
-                std::process::exit(1);
+    /// Catch everything that would need a download with `--offline`, before starting on any
+    /// of it
+    fn check_offline(
+        &self,
+        config: &VoltConfig,
+        target: Target,
+        versions: &[Version],
+    ) -> Result<()> {
+        let downloads = self.downloads_dir(config)?;
+        let install_dir = target.install_dir(&config.node_dir()?);
+        let compression = self.compression.unwrap_or(Compression::DEFAULT);
+
+        let missing: Vec<_> = versions
+            .iter()
+            .filter(|v| {
+                let installed = !self.force
+                    && self.download_only.is_none()
+                    && install_dir.join(v.to_string()).exists();
+                !installed && !downloads.join(target.artifact(v, compression)).exists()
+            })
+            .map(|v| v.to_string())
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(VoltError::NodeNotAvailableOffline {
+                versions: missing.join(", "),
             }
+            .into());
         }
 
-        let mb = MultiProgress::new();
+        Ok(())
+    }
 
-        let handles: Vec<_> = validversions
-            .clone()
-            .into_iter()
-            .map(|i| {
-                let download_url = format!("{download_url}v{i}/node-v{i}-{PLATFORM}-{ARCH}.tar.xz");
+    /// Where artifacts are downloaded to, `--download-only`'s directory if it's given
+    fn downloads_dir(&self, config: &VoltConfig) -> Result<PathBuf> {
+        match &self.download_only {
+            // Verified tarballs are renamed into place in the downloads dir, and left there
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(config.volt_dir()?.join(download::DOWNLOADS_DIR)),
+        }
+    }
 
-                let pb = mb.add(ProgressBar::new_spinner().with_style(
-                    ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}"),
-                ));
+    /// Install the release tarballs given as paths into `node_path`, adding a row for each
+    fn install_local(
+        &self,
+        node_path: &Path,
+        local_artifacts: &[local::LocalArtifact],
+        rows: &mut Vec<report::Row>,
+    ) -> Result<()> {
+        let porcelain = self.porcelain;
+
+        for artifact in local_artifacts {
+            let version = artifact.version.to_string();
+            if self.download_only.is_some() {
+                status!(porcelain, "{} is already on disk", artifact.path.display());
+                continue;
+            }
+            let mut row = report::Row {
+                requested: artifact.path.display().to_string(),
+                version: version.clone(),
+                status: report::Status::AlreadyInstalled,
+                size: None,
+            };
+            if !self.force && node_path.join(&version).exists() {
+                status!(porcelain, "node {version} is already installed");
+                rows.push(row);
+                continue;
+            }
 
-                let handle = tokio::runtime::Handle::current();
+            local::install(artifact, node_path, self.skip_checksum)?;
+            row.status = report::Status::Installed;
+            row.size = std::fs::metadata(&artifact.path).map(|m| m.len()).ok();
+            rows.push(row);
+            status!(
+                porcelain,
+                "Installed node {version} from {}",
+                artifact.path.display()
+            );
+        }
 
-                let node_path = node_path.clone();
+        Ok(())
+    }
 
-                let dir = dir.path().to_owned();
-                handle.spawn_blocking(move || {
-                    if node_path.join(&i.to_string()).exists() {
+    /// Download and install (or only download) `versions`, up to `--jobs` at once
+    ///
+    /// Each version's result is returned once they're all done, with what it did, where it
+    /// was saved with `--download-only`, and the size of its artifact.
+    async fn install_releases(
+        &self,
+        config: &VoltConfig,
+        client: reqwest::Client,
+        target: Target,
+        versions: &[Version],
+    ) -> Result<Vec<Result<(report::Status, Option<PathBuf>, u64)>>> {
+        let porcelain = self.porcelain;
+        let downloads = self.downloads_dir(config)?;
+        let download_only = self.download_only.is_some();
+        if !download_only {
+            std::fs::create_dir_all(target.install_dir(&config.node_dir()?))
+                .map_err(VoltError::CreateDirError)?;
+        }
+        let (working, finished) = if download_only {
+            ("Downloading", "Downloaded")
+        } else {
+            ("Installing", "Installed")
+        };
+
+        // Every artifact is fetched from the first mirror that's up, falling back to the rest
+        let mirrors = Arc::new(mirror::ordered(&client, config).await?);
+
+        // Without progress bars, each version gets a line when it starts and finishes instead
+        let progress = config.show_progress();
+        let mb = if progress {
+            MultiProgress::new()
+        } else {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        };
+        let permits = Arc::new(Semaphore::new(self.jobs.get()));
+
+        // Ctrl-C stops the downloads instead of killing volt halfway through unpacking
+        cancel::listen();
+
+        let mut handles = vec![];
+        for i in versions {
+            let release = Release {
+                downloads: downloads.clone(),
+                skip_checksum: self.skip_checksum,
+                compression: self.compression.unwrap_or(Compression::DEFAULT),
+                ..Release::new(config, client.clone(), mirrors.clone(), target, i.clone())?
+            };
+            let i = i.clone();
+            let force = self.force;
+
+            let pb = mb
+                .add(ProgressBar::new_spinner().with_style(
+                    ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}"),
+                ));
+            pb.set_message(format!(
+                "{:8} {:10}",
+                i.to_string().truecolor(125, 125, 125),
+                "Queued"
+            ));
+
+            let permits = permits.clone();
+
+            // `spawn_blocking` runs on another thread, so the span is entered there
+            let span = tracing::info_span!("install", version = %i);
+            let install = move || {
+                span.in_scope(|| -> Result<(report::Status, Option<PathBuf>, u64)> {
+                    if !force && !download_only && release.dest.exists() {
                         pb.set_message(format!(
                             "{:8} {}",
                             i.to_string().truecolor(0, 255, 0),
                             "Already Installed ✓"
                         ));
                         pb.finish();
-                        return;
+                        if !progress {
+                            status!(porcelain, "node {i} is already installed");
+                        }
+                        return Ok((report::Status::AlreadyInstalled, None, 0));
+                    }
+
+                    if !progress {
+                        status!(porcelain, "{working} node {i}");
                     }
 
                     pb.set_message(format!(
                         "{:8} {:10}",
                         i.to_string().truecolor(125, 125, 125),
-                        String::from("Installing")
+                        working
                     ));
 
                     pb.enable_steady_tick(10);
-                    //println!("Thread {i} starting");
-                    let handle = tokio::runtime::Handle::current();
-                    let response = reqwest::blocking::get(&download_url).unwrap();
-                    let content = response.bytes().unwrap();
-
-                    #[cfg(target_family = "unix")]
-                    {
-                        // Path to write the decompressed tarball to
-                        let fname = download_url.split('/').last().unwrap().to_string();
-                        //let tarpath = &dir.path().join(&fname.strip_suffix(".xz").unwrap());
-                        let tarpath = dir.join(&fname.strip_suffix(".xz").unwrap());
-
-                        // Decompress the tarball
-                        let mut tarball = File::create(&tarpath).unwrap();
-                        tarball
-                            .write_all(&lzma::decompress(&content).unwrap())
-                            .unwrap();
-
-                        // Make sure the first file handle is closed
-                        drop(tarball);
-
-                        // Have to reopen it for reading, File::create() opens for write only
-                        let tarball = File::open(&tarpath).unwrap();
-
-                        // Unpack the tarball
-                        let mut w = tar::Archive::new(tarball);
-                        w.unpack(&node_path).unwrap();
-
-                        // TODO: Find a less disgusting way to do this?
-                        // Grab the name of the folder the tarball will extract to
-                        let p = tarpath
-                            .file_name()
-                            .unwrap()
-                            .to_str()
-                            .to_owned()
-                            .unwrap()
-                            .strip_suffix(".tar")
-                            .unwrap();
-
-                        let from = node_path.join(&p);
-                        let to = node_path.join(&i.to_string());
-
-                        // Rename the folder from the default set by the tarball
-                        // to just the version number
-                        std::fs::rename(from, to);
-                    }
+                    let result = cancel::stop_if_cancelled().and_then(|_| {
+                        if download_only {
+                            let saved = release.download(&pb)?;
+                            let size = std::fs::metadata(&saved).map_or(0, |m| m.len());
+                            Ok((report::Status::Downloaded, Some(saved), size))
+                        } else {
+                            let size = release.install(&pb)?;
+                            Ok((report::Status::Installed, None, size))
+                        }
+                    });
+                    let saved = match result {
+                        Ok(saved) => saved,
+                        Err(e) => {
+                            if cancel::is_cancelled() {
+                                pb.abandon_with_message(format!(
+                                    "{:8} {:10}",
+                                    i.to_string().truecolor(255, 0, 0),
+                                    "Cancelled ✗"
+                                ));
+                            }
+                            return Err(e);
+                        }
+                    };
 
-                    //let size = response.bytes().unwrap().len();
-                    //println!("Got {size} bytes!");
                     pb.set_message(format!(
                         "{:8} {:10}",
                         i.to_string().truecolor(0, 255, 0),
-                        "Installed ✓"
+                        format!("{finished} ✓")
                     ));
                     pb.finish();
-                })
-            })
-            .collect();
+                    if !progress {
+                        status!(porcelain, "{finished} node {i}");
+                    }
 
-        let result = futures::future::join_all(handles).await;
+                    Ok(saved)
+                })
+            };
 
-        Ok(())
-    }
-}
+            handles.push(tokio::spawn(async move {
+                // Hold a permit for the whole download and extraction
+                let _permit = permits.acquire_owned().await.into_diagnostic()?;
 
-fn get_node_dir() -> PathBuf {
-    dirs::data_dir().unwrap().join("volt").join("node")
-}
+                tokio::task::spawn_blocking(install)
+                    .await
+                    .into_diagnostic()?
+            }));
+        }
 
-/// Uninstall a specified version of node
-#[derive(Debug, Parser)]
-pub struct NodeRemove {
-    /// Versions to remove
-    versions: Vec<String>,
+        Ok(futures::future::join_all(handles)
+            .await
+            .into_iter()
+            .map(|result| result.into_diagnostic().and_then(|result| result))
+            .collect())
+    }
 }
 
-#[cfg(unix)]
 #[async_trait]
-impl VoltCommand for NodeRemove {
+impl VoltCommand for NodeInstall {
+    // TODO: Handle errors with file already existing and handle file creation/deletion errors
     async fn exec(self, config: VoltConfig) -> Result<()> {
-        if self.versions.is_empty() {
-            NodeRemove::command()
-                .error(
-                    ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand,
-                    "Must have at least one version",
-                )
-                .exit();
-        }
-
-        let node_dir = get_node_dir();
-
-        let current_dir = if node_dir.join("current").exists() {
-            let curr = std::fs::canonicalize(node_dir.join("current"))
-                .unwrap()
-                .parent()
-                .unwrap()
-                .to_owned();
-            Some(curr)
-        } else {
-            None
+        let porcelain = self.porcelain;
+        let target = Target {
+            os: self.platform.unwrap_or(PLATFORM),
+            arch: self.arch.unwrap_or(ARCH),
         };
 
-        let current_version = current_dir
-            .as_ref()
-            .map(|dir| dir.file_name().unwrap().to_str().unwrap());
+        if self.interactive {
+            let releases = fetch_node_versions(&config, &config.http_client()?).await?;
+            let versions = pick::versions(releases, &target.key())?;
+            if versions.is_empty() {
+                status!(porcelain, "Nothing selected, nothing to install");
+                return Ok(());
+            }
 
-        // FIXME: This is just to meet a spec we made for class, remove after like May 9th
-        //
-        // This is just here to satisfy a requirement for a class, need to do this for a grade.
-        // Will remove after class is over - brokenbyte
+            // The picked versions go through the install like any others
+            return NodeInstall {
+                versions,
+                interactive: false,
+                ..self
+            }
+            .exec(config)
+            .await;
+        }
+        tracing::debug!(
+            "On platform '{}' and arch '{}', installing for '{}'",
+            PLATFORM,
+            ARCH,
+            target
+        );
+
+        // Release tarballs on disk are installed as they are, without asking the mirror
+        let mut local_artifacts = vec![];
+        let mut remote = vec![];
         for v in &self.versions {
-            let ver = v.parse::<Version>();
-            match ver {
-                Ok(_) => {}
-                Err(_) => {
-                    eprintln!("{v} is not a valid version");
-                    std::process::exit(1);
+            match local::artifact(v)? {
+                Some(artifact) => local_artifacts.push(artifact),
+                None => remote.push(v.as_str()),
+            }
+        }
+
+        let client = config.http_client()?;
+        let node_versions = if remote.is_empty() && self.lts.is_none() {
+            vec![]
+        } else {
+            fetch_node_versions(&config, &client).await?
+        };
+        // Sorted once, every request below is resolved against it
+        let releases = Releases::new(&node_versions);
+
+        let Resolved {
+            versions,
+            requested_as,
+            satisfied,
+            mut rows,
+        } = self.resolve(&config, &releases, target, &remote)?;
+
+        // Everything may have been skipped, e.g. on 32 bit systems
+        if versions.is_empty() && local_artifacts.is_empty() && satisfied.is_empty() {
+            return Ok(());
+        }
+
+        check_builds(&releases, &versions, target)?;
+        if config.offline() {
+            self.check_offline(&config, target, &versions)?;
+        }
+
+        if self.dry_run {
+            return self.print_plan(&config, target, &versions, &local_artifacts, &satisfied);
+        }
+
+        let node_path = config.node_dir()?;
+        std::fs::create_dir_all(&node_path).map_err(VoltError::CreateDirError)?;
+
+        self.install_local(&node_path, &local_artifacts, &mut rows)?;
+
+        let install_dir = target.install_dir(&node_path);
+        let results = self
+            .install_releases(&config, client, target, &versions)
+            .await?;
+
+        // Let every download finish before reporting, so one failure doesn't hide the others
+        let mut installed = vec![];
+        let mut saved = vec![];
+        let mut failed = vec![];
+        for ((version, requested), result) in versions.iter().zip(&requested_as).zip(results) {
+            if cancel::is_cancelled() {
+                continue;
+            }
+
+            let mut row = report::Row {
+                requested: requested.clone(),
+                version: version.to_string(),
+                status: report::Status::Failed,
+                size: None,
+            };
+            match result {
+                Ok((status, path, size)) => {
+                    installed.push(version.to_string());
+                    saved.extend(path);
+                    row.status = status;
+                    row.size = (status != report::Status::AlreadyInstalled).then(|| size);
+                }
+                Err(e) => {
+                    let causes = e.chain().map(|cause| cause.to_string()).collect::<Vec<_>>();
+                    eprintln!("{} node {version}: {}", "error:".red(), causes.join(": "));
+                    failed.push(version.to_string());
                 }
             }
+            rows.push(row);
+        }
 
-            if !node_dir.join(&v).exists() {
-                eprintln!("Version {v} not installed");
-                std::process::exit(1);
+        // Every download has stopped and cleaned up its staging directory by now
+        if cancel::is_cancelled() {
+            // The bars leave the cursor at the end of the last one
+            if config.show_progress() {
+                eprintln!();
             }
+            return Err(VoltError::NodeInstallCancelled.into());
         }
 
-        for v in self.versions {
-            let version_dir = node_dir.join(&v);
+        // The bars are gone once they finish, this is what's left of them
+        if !porcelain && !config.quiet() && !rows.is_empty() {
+            if config.show_progress() {
+                println!();
+            }
+            report::print(&rows);
+        }
 
-            /*
-             * FIXME: Uncomment this after removing the above for loop :)
-             *if !version_dir.exists() {
-             *    eprintln!("Version {v} not installed");
-             *    continue;
-             *}
-             */
+        if !failed.is_empty() {
+            let help = if installed.is_empty() {
+                "nothing was installed".to_string()
+            } else {
+                format!("installed {}", installed.join(", "))
+            };
 
-            if matches!(current_version, Some(ver) if ver == v) {
-                let current_dir = current_dir.as_ref().unwrap();
-                let current_bin = std::fs::read_dir(current_dir.join("bin")).unwrap();
+            return Err(VoltError::NodeInstallFailed {
+                failed: failed.join(", "),
+                help,
+            }
+            .into());
+        }
 
-                // Remove all the installed symlinks
-                for binary in current_bin {
-                    let b = binary.unwrap();
-                    std::fs::remove_file(dirs::executable_dir().unwrap().join(b.file_name()));
+        if self.download_only.is_some() {
+            for path in &saved {
+                if porcelain {
+                    println!("{}", path.display());
+                } else {
+                    println!("Saved {}", path.display());
                 }
+            }
+            return Ok(());
+        }
+
+        if !target.is_native() && !versions.is_empty() {
+            status!(
+                porcelain,
+                "Installed the {target} builds in {}, they can't be used on this machine",
+                install_dir.display()
+            );
+        }
 
-                std::fs::remove_file(node_dir.join("current"));
+        // Every version asked for is installed now, whether or not this run installed it
+        let requested = versions
+            .iter()
+            .chain(local_artifacts.iter().map(|artifact| &artifact.version))
+            .chain(&satisfied)
+            .collect::<Vec<_>>();
+
+        if self.default {
+            let newest = requested.iter().max().unwrap().to_string();
+            if requested.len() > 1 {
+                status!(
+                    porcelain,
+                    "Switching to {newest}, the newest of the requested versions"
+                );
             }
 
-            // Always remove the version directory, regardless of current version status
-            std::fs::remove_dir_all(node_dir.join(v));
+            NodeUse {
+                version: Some(newest),
+                shell: None,
+                force: false,
+                enable_corepack: false,
+                registry: None,
+                silent: false,
+                verbose: false,
+                porcelain,
+            }
+            .run(config)
+            .await?;
         }
+
+        if porcelain {
+            for version in &requested {
+                println!("{version}");
+            }
+        }
+
         Ok(())
     }
 }
 
-#[cfg(windows)]
-#[async_trait]
-impl VoltCommand for NodeRemove {
-    async fn exec(self, config: VoltConfig) -> Result<()> {
-        if self.versions.len() == 0 {
-            NodeRemove::command()
-                .error(
-                    ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand,
-                    "Must have at least one version",
-                )
-                .exit();
+/// Directory holding the binaries of an installed version
+fn get_bin_dir(config: &VoltConfig, version: &str) -> Result<PathBuf> {
+    let version_dir = config.node_dir()?.join(version);
+
+    // Windows releases keep their binaries at the root of the version directory
+    if cfg!(windows) {
+        Ok(version_dir)
+    } else {
+        Ok(version_dir.join("bin"))
+    }
+}
+
+/// Every installed version, oldest first, or nothing if the node dir doesn't exist yet
+///
+/// This is the one place that decides what counts as an installed version, commands choose
+/// their own messaging when it's empty.
+pub(crate) fn installed_versions(config: &VoltConfig) -> Result<Vec<Version>> {
+    Ok(versions_in(&config.node_dir()?))
+}
+
+/// The versions installed in `dir`, oldest first
+///
+/// `current`, cross-installs, staging directories and anything else that isn't a version are
+/// skipped.
+fn versions_in(dir: &Path) -> Vec<Version> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut versions = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let version = name.to_str()?.parse::<Version>();
+            if version.is_err() {
+                tracing::debug!("Skipping {:?}, it isn't a node version", entry.path());
+            }
+            version.ok()
+        })
+        .collect::<Vec<_>>();
+
+    versions.sort();
+    versions.dedup();
+    versions
+}
+
+/// Resolve a requested version to the name of an installed version directory
+///
+/// Exact versions are returned as-is, partial versions like `18` and other ranges pick the
+/// newest installed match, and anything else is looked up as an alias. `latest` picks the
+/// newest installed version and `lts`, `lts/*` or `lts/<codename>` pick the newest installed
+/// LTS release.
+async fn resolve_installed(requested: &str, config: &VoltConfig) -> Result<String> {
+    let requested = unalias(config, requested)?;
+    let requested = strip_v(&requested);
+
+    if config.node_dir()?.join(requested).exists() {
+        return Ok(requested.to_string());
+    }
+
+    let installed = installed_versions(config)?;
+
+    let lts_codename = match requested {
+        "lts" => Some("*"),
+        _ => requested.strip_prefix("lts/"),
+    };
+    let is_alias = requested == "latest" || lts_codename.is_some();
+
+    let resolved = if requested == "latest" {
+        installed.last().cloned()
+    } else if let Some(codename) = lts_codename {
+        let lts = fetch_node_versions(config, &config.http_client()?)
+            .await?
+            .into_iter()
+            .filter(|n| match &n.lts {
+                Some(lts) => codename == "*" || lts.eq_ignore_ascii_case(codename),
+                None => false,
+            })
+            .map(|n| n.version)
+            .collect::<Vec<_>>();
+
+        installed.iter().filter(|v| lts.contains(v)).max().cloned()
+    } else if let Some(range) = partial_range(requested).or_else(|| requested.parse().ok()) {
+        installed
+            .iter()
+            .filter(|v| v.satisfies(&range))
+            .max()
+            .cloned()
+    } else {
+        None
+    };
+
+    if resolved.is_none() && is_alias {
+        let mut available = installed.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        if available.is_empty() {
+            available.push("none".to_string());
         }
 
-        let usedversion = std::fs::read_to_string(get_node_dir().join("current")).unwrap();
+        return Err(VoltError::NodeNoInstalledMatch {
+            requested: requested.to_string(),
+            available: available.join(", "),
+        }
+        .into());
+    }
 
-        for version in self.versions {
-            let node_path = get_node_dir().join(&version);
+    Ok(resolved
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| requested.to_string()))
+}
 
-            println!("{}", node_path.display());
+/// The version the `current` symlink points at, if one has been selected
+#[cfg(unix)]
+fn current_version(config: &VoltConfig) -> Result<Option<String>> {
+    // `current` links to `<version>/bin`, so the version is the name of the parent directory
+    let bin = match std::fs::canonicalize(config.current_marker()?) {
+        Ok(bin) => bin,
+        Err(_) => return Ok(None),
+    };
+
+    Ok(bin
+        .parent()
+        .and_then(|version_dir| version_dir.file_name())
+        .map(|name| name.to_string_lossy().into_owned()))
+}
 
-            if node_path.exists() {
-                fs::remove_dir_all(&node_path).await.unwrap();
-                println!("Removed version {version}");
+/// The version recorded in the `current` file written by `volt node use`, if any
+#[cfg(windows)]
+fn current_version(config: &VoltConfig) -> Result<Option<String>> {
+    Ok(std::fs::read_to_string(config.current_marker()?)
+        .ok()
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty()))
+}
+
+/// The selected version, if it's still installed
+///
+/// A `current` marker left pointing at a version directory that has since been deleted means
+/// there's no active version rather than a broken one. It's left alone, this only reads the
+/// data directory; [`remove_stale_current`] cleans it up.
+pub(crate) fn active_version(config: &VoltConfig) -> Result<Option<String>> {
+    Ok(current_version(config)?.filter(|version| {
+        config
+            .node_dir()
+            .map_or(false, |node_dir| node_dir.join(version).exists())
+    }))
+}
+
+/// Remove a `current` marker left pointing at a version that's no longer installed,
+/// returning whether there was one
+///
+/// The data directory is only locked once there's something to remove, and the marker is
+/// checked again under the lock since a `volt node use` may have replaced it meanwhile.
+pub(crate) async fn remove_stale_current(config: &VoltConfig) -> Result<bool> {
+    let marker = config.current_marker()?;
+    let is_stale = || -> Result<bool> {
+        Ok(std::fs::symlink_metadata(&marker).is_ok() && active_version(config)?.is_none())
+    };
+    if !is_stale()? {
+        return Ok(false);
+    }
+
+    let _lock = lock::acquire(config).await?;
+    if !is_stale()? {
+        return Ok(false);
+    }
+
+    std::fs::remove_file(&marker)
+        .into_diagnostic()
+        .wrap_err(format!("failed to remove stale {}", marker.display()))?;
+
+    eprintln!(
+        "{} removed {}, it pointed at a version that's no longer installed",
+        "warning:".yellow(),
+        marker.display()
+    );
+
+    Ok(true)
+}
+
+/// Uninstall versions of node
+///
+/// Versions may be exact (`18.19.0`) or ranges (`'<16'`) matched against the installed
+/// versions.
+#[derive(Debug, Parser)]
+pub struct NodeRemove {
+    /// Versions or ranges to remove
+    #[clap(required_unless_present_any = &["all", "all-except-current"])]
+    versions: Vec<String>,
+
+    /// Remove every installed version, including the one in use
+    #[clap(long, conflicts_with_all = &["versions", "all-except-current"])]
+    all: bool,
+
+    /// Remove every installed version except the one in use
+    #[clap(long, conflicts_with = "versions")]
+    all_except_current: bool,
+
+    /// Remove the version in use as well, leaving no active version
+    #[clap(long)]
+    force: bool,
+
+    /// Don't ask for confirmation
+    #[clap(short, long)]
+    yes: bool,
+}
+
+impl NodeRemove {
+    /// The installed versions to remove, along with the arguments that matched nothing
+    fn targets(
+        &self,
+        config: &VoltConfig,
+        current: Option<&str>,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let installed = installed_versions(config)?;
+        if installed.is_empty() {
+            return Err(VoltError::NodeNoVersionsInstalled.into());
+        }
+        let installed = installed.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        if self.all || self.all_except_current {
+            let targets = installed
+                .into_iter()
+                .filter(|v| self.all || current != Some(v.as_str()))
+                .collect();
+            return Ok((targets, vec![]));
+        }
+
+        let mut targets: Vec<String> = vec![];
+        let mut skipped = vec![];
+
+        for v in &self.versions {
+            let v = &strip_v(v).to_string();
+            let matched = if v.parse::<Version>().is_ok() {
+                if installed.contains(v) {
+                    vec![v.clone()]
+                } else {
+                    eprintln!(
+                        "{} {}",
+                        "warning:".yellow(),
+                        VoltError::NodeVersionNotInstalled { version: v.clone() }
+                    );
+                    vec![]
+                }
+            } else if let Ok(range) = v.parse::<Range>() {
+                let matched = installed
+                    .iter()
+                    .filter(|installed| {
+                        installed
+                            .parse::<Version>()
+                            .map_or(false, |i| i.satisfies(&range))
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                if matched.is_empty() {
+                    eprintln!("{} no installed version matches {}", "warning:".yellow(), v);
+                }
+                matched
             } else {
-                println!(
-                    "Failed to remove NodeJS version {version}.\nThat version was not installed."
+                eprintln!(
+                    "{} {}",
+                    "warning:".yellow(),
+                    VoltError::NodeInvalidVersion { version: v.clone() }
                 );
+                vec![]
+            };
+
+            if matched.is_empty() {
+                skipped.push(v.clone());
             }
 
-            if usedversion == version {
-                std::fs::remove_file(Path::new(&get_node_dir().join("node.exe")));
+            // The current version is only removed when asked for explicitly
+            for version in matched {
+                if !self.force && current == Some(version.as_str()) {
+                    eprintln!(
+                        "{} {}",
+                        "warning:".yellow(),
+                        VoltError::NodeVersionInUse {
+                            version: version.clone()
+                        }
+                    );
+                    skipped.push(version);
+                } else if !targets.contains(&version) {
+                    targets.push(version);
+                }
             }
         }
 
+        Ok((targets, skipped))
+    }
+}
+
+#[async_trait]
+impl VoltCommand for NodeRemove {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let link_dir = config.bin_dir()?;
+        let current = current_version(&config)?;
+        let (targets, skipped) = self.targets(&config, current.as_deref())?;
+
+        if !targets.is_empty() {
+            let binaries = targets
+                .iter()
+                .map(|v| linked_binaries(&config, v, &link_dir))
+                .sum::<Result<usize>>()?;
+
+            let message = match binaries {
+                0 => format!("Remove node {}?", targets.join(", ")),
+                n => format!(
+                    "Remove node {} and {n} linked binaries?",
+                    targets.join(", ")
+                ),
+            };
+
+            if !confirm(&message, self.yes)? {
+                return Ok(());
+            }
+        }
+
+        for v in &targets {
+            remove_version(&config, v, &link_dir)?;
+            println!("Removed node {v}");
+        }
+
+        if !skipped.is_empty() {
+            return Err(VoltError::NodeVersionsNotRemoved {
+                versions: skipped.join(", "),
+            }
+            .into());
+        }
+
         Ok(())
     }
 }
 
-#[cfg(windows)]
-async fn use_windows(version: String) {
-    let node_path = get_node_dir().join(&version).join("node.exe");
-    let path = Path::new(&node_path);
+/// Ask before doing something destructive, defaulting to no
+///
+/// The prompt is skipped when `yes` is set or stdout isn't a terminal, so scripts keep working.
+fn confirm(message: &str, yes: bool) -> Result<bool> {
+    if yes || !dialoguer::console::Term::stdout().is_term() {
+        return Ok(true);
+    }
 
-    if path.exists() {
-        println!("Using version {}", version);
+    Confirm {
+        message: message.into(),
+        default: false,
+    }
+    .run()
+    .into_diagnostic()
+}
 
-        let link_dir = dirs::data_dir()
-            .unwrap()
-            .join("volt")
-            .join("bin")
-            .into_os_string()
-            .into_string()
-            .unwrap();
+/// How many links in `link_dir` removing `version` would delete
+#[cfg(unix)]
+fn linked_binaries(config: &VoltConfig, version: &str, link_dir: &Path) -> Result<usize> {
+    if current_version(config)?.as_deref() != Some(version) {
+        return Ok(0);
+    }
 
-        let link_file = dirs::data_dir()
-            .unwrap()
-            .join("volt")
-            .join("bin")
-            .join("node.exe");
-        let link_file = Path::new(&link_file);
+    let node_dir = config.node_dir()?;
+    let binaries = match std::fs::read_dir(node_dir.join(version).join("bin")) {
+        Ok(binaries) => binaries,
+        Err(_) => return Ok(0),
+    };
+
+    Ok(binaries
+        .flatten()
+        .filter(|binary| {
+            std::fs::read_link(link_dir.join(binary.file_name()))
+                .map_or(false, |target| target.starts_with(&node_dir))
+        })
+        .count())
+}
 
-        if link_file.exists() {
-            fs::remove_file(link_file).await.unwrap();
+/// How many shims in `link_dir` removing `version` would delete
+#[cfg(windows)]
+fn linked_binaries(config: &VoltConfig, version: &str, link_dir: &Path) -> Result<usize> {
+    if current_version(config)?.as_deref() != Some(version) {
+        return Ok(0);
+    }
+
+    Ok(windows_shims(&config.node_dir()?.join(version))
+        .iter()
+        .filter_map(|shim| shim.file_name())
+        .filter(|name| link_dir.join(name).exists())
+        .count())
+}
+
+/// Remove an installed version, unlinking its binaries from `link_dir` if it's the current one
+#[cfg(unix)]
+fn remove_version(config: &VoltConfig, version: &str, link_dir: &Path) -> Result<()> {
+    let node_dir = config.node_dir()?;
+    let version_dir = node_dir.join(version);
+
+    if current_version(config)?.as_deref() == Some(version) {
+        // Remove all the installed symlinks, leaving anything volt didn't create
+        if let Ok(binaries) = std::fs::read_dir(version_dir.join("bin")) {
+            for binary in binaries.flatten() {
+                let link = link_dir.join(binary.file_name());
+                match remove_link(&link, &node_dir, false) {
+                    Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => {
+                        return Err(e)
+                            .into_diagnostic()
+                            .wrap_err(format!("failed to remove {}", link.display()));
+                    }
+                    _ => {}
+                }
+            }
         }
 
-        let newfile = std::fs::copy(node_path, link_file);
+        let current = config.current_marker()?;
+        std::fs::remove_file(&current)
+            .into_diagnostic()
+            .wrap_err(format!("failed to remove {}", current.display()))?;
+    }
+
+    // Always remove the version directory, regardless of current version status
+    std::fs::remove_dir_all(&version_dir)
+        .into_diagnostic()
+        .wrap_err(format!("failed to remove {}", version_dir.display()))
+}
 
-        match newfile {
-            Ok(_) => {}
-            Err(_) => {
-                println!("Sorry, something went wrong.");
-                return;
+/// Remove an installed version, unlinking its shims from `link_dir` if it's the current one
+#[cfg(windows)]
+fn remove_version(config: &VoltConfig, version: &str, link_dir: &Path) -> Result<()> {
+    let version_dir = config.node_dir()?.join(version);
+    let shims = windows_shims(&version_dir);
+
+    std::fs::remove_dir_all(&version_dir)
+        .into_diagnostic()
+        .wrap_err(format!("failed to remove {}", version_dir.display()))?;
+
+    // A missing `current` file just means no version was ever selected
+    if current_version(config)?.as_deref() == Some(version) {
+        let links = shims
+            .iter()
+            .filter_map(|shim| shim.file_name())
+            .map(|name| link_dir.join(name));
+
+        for path in links.chain(Some(config.current_marker()?)) {
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(e)
+                        .into_diagnostic()
+                        .wrap_err(format!("failed to remove {}", path.display()));
+                }
+                _ => {}
             }
         }
+    }
+
+    Ok(())
+}
 
-        let vfpath = dirs::data_dir().unwrap().join("volt").join("current");
-        let vfpath = Path::new(&vfpath);
-        let vfile = std::fs::write(vfpath, version);
+/// Total size in bytes of the files under `path`, not following symlinks
+fn dir_size(path: &Path) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
 
-        let path = env::var("PATH").unwrap();
-        if !path.contains(&link_dir) {
-            let command = format!("[Environment]::SetEnvironmentVariable('Path', [Environment]::GetEnvironmentVariable('Path', 'User') + '{}', 'User')", &link_dir);
-            Command::new("Powershell")
-                .args(&["-Command", &command])
-                .output()
-                .unwrap();
-            println!("PATH environment variable updated.\nYou will need to restart your terminal for changes to apply.");
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or_default()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn dangling_current_link_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VoltConfig::parse_from(["volt", "--data-dir", dir.path().to_str().unwrap()]);
+
+        let node_dir = config.node_dir().unwrap();
+        std::fs::create_dir_all(&node_dir).unwrap();
+        let current = config.current_marker().unwrap();
+        std::os::unix::fs::symlink(node_dir.join("18.19.0").join("bin"), &current).unwrap();
+
+        // Reading the active version never changes anything
+        assert_eq!(active_version(&config).unwrap(), None);
+        assert!(std::fs::symlink_metadata(&current).is_ok());
+
+        assert!(remove_stale_current(&config).await.unwrap());
+        assert!(std::fs::symlink_metadata(&current).is_err());
+        assert!(!remove_stale_current(&config).await.unwrap());
+
+        // `volt node current` cleans it up too, rather than printing a missing version
+        std::os::unix::fs::symlink(node_dir.join("18.19.0").join("bin"), &current).unwrap();
+        let err = NodeCurrent {}.exec(config.clone()).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<VoltError>(),
+            Some(VoltError::NodeNoVersionSelected)
+        ));
+        assert!(std::fs::symlink_metadata(&current).is_err());
+    }
+
+    #[test]
+    fn empty_data_dir_has_no_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = VoltConfig::parse_from(["volt", "--data-dir", dir.path().to_str().unwrap()]);
+
+        // Before anything is installed the node dir doesn't even exist
+        assert!(installed_versions(&config).unwrap().is_empty());
+
+        let node_dir = config.node_dir().unwrap();
+        for name in ["current", ".staging-abc", "18.19.0", "16.20.2"] {
+            std::fs::create_dir_all(node_dir.join(name)).unwrap();
         }
-    } else {
-        println!("That version of node is not installed!\nTry \"volt node install {}\" to install that version.", version);
+        let installed = installed_versions(&config).unwrap();
+        assert_eq!(
+            installed.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+            ["16.20.2", "18.19.0"]
+        );
     }
 }
@@ -0,0 +1,168 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Named aliases for node versions
+
+use std::{collections::BTreeMap, path::Path};
+
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use node_semver::Range;
+
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Name of the alias file, stored in the data directory
+pub(crate) const ALIASES_FILE: &str = "node-aliases.json";
+
+/// Alias names mapped to the version they stand for
+type Aliases = BTreeMap<String, String>;
+
+/// Name a node version, e.g. `volt node alias work 16`
+#[derive(Debug, Parser)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct NodeAlias {
+    #[clap(subcommand)]
+    cmd: Option<AliasCommand>,
+
+    /// Name of the alias
+    #[clap(required = true)]
+    name: Option<String>,
+
+    /// Version (or range, or another alias) the name stands for
+    #[clap(required = true)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum AliasCommand {
+    /// List every alias
+    List,
+    /// Remove an alias
+    Rm {
+        /// Name of the alias to remove
+        name: String,
+    },
+}
+
+#[async_trait]
+impl VoltCommand for NodeAlias {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let path = config.volt_dir()?.join(ALIASES_FILE);
+        let mut aliases = read(&path)?;
+
+        match self.cmd {
+            Some(AliasCommand::List) => {
+                for (name, version) in &aliases {
+                    println!("{name} -> {version}");
+                }
+            }
+            Some(AliasCommand::Rm { name }) => {
+                if aliases.remove(&name).is_none() {
+                    return Err(VoltError::NodeAliasNotFound { name }.into());
+                }
+                write(&path, &aliases)?;
+            }
+            None => {
+                // Both are required unless a subcommand is given
+                let name = self.name.unwrap();
+                let version = self.version.unwrap();
+
+                if is_reserved(&name) {
+                    return Err(VoltError::NodeInvalidAliasName { name }.into());
+                }
+
+                println!("{name} -> {version}");
+                aliases.insert(name, version);
+                write(&path, &aliases)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Names that would be ambiguous with a version, range or builtin alias
+fn is_reserved(name: &str) -> bool {
+    name == "latest" || name == "lts" || name.starts_with("lts/") || name.parse::<Range>().is_ok()
+}
+
+fn read(path: &Path) -> Result<Aliases> {
+    match std::fs::read(path) {
+        Ok(contents) => serde_json::from_slice(&contents)
+            .into_diagnostic()
+            .wrap_err(format!("failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Aliases::new()),
+        Err(e) => Err(VoltError::ReadFileError {
+            source: e,
+            name: path.display().to_string(),
+        }
+        .into()),
+    }
+}
+
+fn write(path: &Path, aliases: &Aliases) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(VoltError::CreateDirError)?;
+    }
+
+    let contents = serde_json::to_vec_pretty(aliases).into_diagnostic()?;
+
+    std::fs::write(path, contents).map_err(|e| {
+        VoltError::WriteFileError {
+            source: e,
+            name: path.display().to_string(),
+        }
+        .into()
+    })
+}
+
+/// Follow `name` through the alias file, returning it unchanged if it isn't an alias
+///
+/// An alias may point at another alias, but only one level deep so cycles can't loop forever.
+pub fn resolve(config: &VoltConfig, name: &str) -> Result<String> {
+    let aliases = read(&config.volt_dir()?.join(ALIASES_FILE))?;
+
+    Ok(lookup(&aliases, name).to_string())
+}
+
+fn lookup<'a>(aliases: &'a Aliases, name: &'a str) -> &'a str {
+    match aliases.get(name) {
+        Some(target) => aliases.get(target).unwrap_or(target),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_chain_one_level() {
+        let aliases: Aliases = [("work", "legacy"), ("legacy", "16"), ("a", "b"), ("b", "a")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        assert_eq!(lookup(&aliases, "legacy"), "16");
+        assert_eq!(lookup(&aliases, "work"), "16");
+        assert_eq!(lookup(&aliases, "a"), "a");
+        assert_eq!(lookup(&aliases, "18.19.0"), "18.19.0");
+    }
+}
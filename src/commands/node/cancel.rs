@@ -0,0 +1,64 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Cancelling installs with Ctrl-C
+//!
+//! Once [`listen`] is called, Ctrl-C only sets a flag. Downloads stop waiting for the mirror
+//! and extraction stops between entries, so the install can clean up after itself before
+//! volt exits.
+
+use lazy_static::lazy_static;
+use miette::Result;
+use tokio::sync::watch;
+
+use crate::core::utils::errors::VoltError;
+
+lazy_static! {
+    static ref CANCELLED: watch::Sender<bool> = watch::channel(false).0;
+}
+
+/// Handle Ctrl-C from now on by cancelling instead of exiting straight away
+pub fn listen() {
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            // Only fails without receivers, `is_cancelled` reads the value anyway
+            let _ = CANCELLED.send(true);
+        }
+    });
+}
+
+pub fn is_cancelled() -> bool {
+    *CANCELLED.borrow()
+}
+
+/// Fail with [`VoltError::NodeInstallCancelled`] if Ctrl-C was pressed
+pub fn stop_if_cancelled() -> Result<()> {
+    if is_cancelled() {
+        return Err(VoltError::NodeInstallCancelled.into());
+    }
+
+    Ok(())
+}
+
+/// Resolves once Ctrl-C was pressed
+pub async fn cancelled() {
+    let mut rx = CANCELLED.subscribe();
+
+    while !*rx.borrow() {
+        // The sender lives in a static and is never dropped
+        let _ = rx.changed().await;
+    }
+}
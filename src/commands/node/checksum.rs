@@ -0,0 +1,111 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Verify downloaded node artifacts against the release's `SHASUMS256.txt`
+
+use std::{fs::File, io, path::Path};
+
+use miette::Result;
+use sha2::{Digest, Sha256};
+
+use crate::core::utils::errors::VoltError;
+
+/// Name of the checksum file published in every release directory on the mirror
+pub const SHASUMS_FILE: &str = "SHASUMS256.txt";
+
+/// Find the expected sha256 for `filename` in the contents of a `SHASUMS256.txt`
+///
+/// Each line has the form `<hex digest>  <file name>`.
+pub fn find_checksum<'a>(shasums: &'a str, filename: &str) -> Option<&'a str> {
+    shasums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some(hash), Some(name)) if name == filename => Some(hash),
+            _ => None,
+        }
+    })
+}
+
+/// Hex encoded sha256 digest of `data`
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Hex encoded sha256 digest of the file at `path`, read a chunk at a time so large
+/// downloads are never held in memory
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Check that `actual`, a hex encoded sha256 digest, is the one listed for `filename` in
+/// `shasums`
+pub fn verify(shasums: &str, filename: &str, actual: &str) -> Result<()> {
+    let expected =
+        find_checksum(shasums, filename).ok_or_else(|| VoltError::NodeChecksumMissing {
+            file: filename.to_string(),
+        })?;
+
+    if !expected.eq_ignore_ascii_case(actual) {
+        return Err(VoltError::NodeChecksumMismatch {
+            file: filename.to_string(),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHASUMS: &str = "\
+a1b2c3  node-v18.19.0-darwin-arm64.tar.xz
+2e3b3e0e7a1c2b8a7d6d47c1cd1e1e0b9a8f1b1f8c1c0e4f0b4b2f5e0c8f6a3d  node-v18.19.0-linux-x64.tar.xz
+";
+
+    #[test]
+    fn finds_checksum_for_exact_file_name() {
+        assert_eq!(
+            find_checksum(SHASUMS, "node-v18.19.0-darwin-arm64.tar.xz"),
+            Some("a1b2c3")
+        );
+        assert_eq!(
+            find_checksum(SHASUMS, "node-v18.19.0-darwin-arm64.tar"),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_data() {
+        let data = b"not node";
+        let shasums = format!("{}  node.tar.xz\n", sha256_hex(data));
+
+        assert!(verify(&shasums, "node.tar.xz", &sha256_hex(data)).is_ok());
+        assert!(verify(&shasums, "node.tar.xz", &sha256_hex(b"tampered")).is_err());
+        assert!(verify(&shasums, "other.tar.xz", &sha256_hex(data)).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.tar.xz");
+        std::fs::write(&path, data).unwrap();
+        assert_eq!(sha256_file(&path).unwrap(), sha256_hex(data));
+    }
+}
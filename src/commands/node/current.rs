@@ -0,0 +1,41 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use async_trait::async_trait;
+use clap::Parser;
+use miette::Result;
+
+use super::remove_stale_current;
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Print the active node version
+#[derive(Debug, Parser)]
+pub struct NodeCurrent {}
+
+#[async_trait]
+impl VoltCommand for NodeCurrent {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        remove_stale_current(&config).await?;
+        let version = crate::node::current(&config)?.ok_or(VoltError::NodeNoVersionSelected)?;
+
+        println!("{version}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Diagnose why `volt node use` doesn't seem to work
+
+use std::{env, path::PathBuf, process::Command};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+
+use super::{
+    active_version, get_bin_dir, installed_versions, is_on_path, remove_stale_current, verify,
+};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Check that volt's node setup works, with hints for anything that doesn't
+///
+/// Every installed version is asked for its `node` and `npm` versions, and flagged if `node`
+/// isn't the version its directory is named after.
+#[derive(Debug, Parser)]
+pub struct NodeDoctor {}
+
+/// Outcome of a single check
+struct Check {
+    ok: bool,
+    message: String,
+    /// How to fix a failed check
+    hint: Option<String>,
+}
+
+impl Check {
+    fn pass(message: String) -> Self {
+        Self {
+            ok: true,
+            message,
+            hint: None,
+        }
+    }
+
+    fn fail(message: String, hint: String) -> Self {
+        Self {
+            ok: false,
+            message,
+            hint: Some(hint),
+        }
+    }
+
+    fn print(&self) {
+        if self.ok {
+            println!("{} {}", "✓".truecolor(0, 255, 0), self.message);
+        } else {
+            println!("{} {}", "✗".truecolor(255, 0, 0), self.message);
+        }
+
+        if let Some(hint) = &self.hint {
+            println!("  {hint}");
+        }
+    }
+}
+
+#[async_trait]
+impl VoltCommand for NodeDoctor {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        remove_stale_current(&config).await?;
+        let current = active_version(&config)?;
+
+        let mut checks = vec![
+            check_data_dir(&config)?,
+            check_bin_dir(&config)?,
+            check_current(current.as_deref()),
+            check_node_on_path(current.as_deref()),
+        ];
+        for version in installed_versions(&config)? {
+            checks.push(check_installed(&config, &version.to_string())?);
+        }
+
+        for check in &checks {
+            check.print();
+        }
+
+        let failed = checks.iter().filter(|check| !check.ok).count();
+        if failed > 0 {
+            return Err(VoltError::NodeDoctorFailed { failed }.into());
+        }
+
+        Ok(())
+    }
+}
+
+fn check_data_dir(config: &VoltConfig) -> Result<Check> {
+    let dir = config.volt_dir()?;
+
+    if !dir.exists() {
+        return Ok(Check::fail(
+            format!("{} doesn't exist", dir.display()),
+            "run `volt node install <version>` to create it".to_string(),
+        ));
+    }
+
+    Ok(match tempfile::tempfile_in(&dir) {
+        Ok(_) => Check::pass(format!("{} is writable", dir.display())),
+        Err(e) => Check::fail(
+            format!("{} isn't writable: {}", dir.display(), e),
+            "fix its permissions, or point `VOLT_HOME` somewhere else".to_string(),
+        ),
+    })
+}
+
+fn check_bin_dir(config: &VoltConfig) -> Result<Check> {
+    let dir = config.bin_dir()?;
+
+    if is_on_path(&dir) {
+        return Ok(Check::pass(format!("{} is on PATH", dir.display())));
+    }
+
+    let hint = if cfg!(windows) {
+        "run `volt node use <version>` to add it to your user PATH".to_string()
+    } else {
+        format!(
+            "add `export PATH=\"{}:$PATH\"` to your shell's startup file",
+            dir.display()
+        )
+    };
+
+    Ok(Check::fail(
+        format!("{} isn't on PATH", dir.display()),
+        hint,
+    ))
+}
+
+fn check_current(current: Option<&str>) -> Check {
+    match current {
+        Some(version) => Check::pass(format!("node {version} is selected")),
+        None => Check::fail(
+            "no version of node is selected".to_string(),
+            "run `volt node use <version>` to select one".to_string(),
+        ),
+    }
+}
+
+/// First `node` binary on `PATH`, which is the one a shell would run
+fn node_on_path() -> Option<PathBuf> {
+    let name = if cfg!(windows) { "node.exe" } else { "node" };
+
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn check_node_on_path(current: Option<&str>) -> Check {
+    let node = match node_on_path() {
+        Some(node) => node,
+        None => {
+            return Check::fail(
+                "there's no `node` on PATH".to_string(),
+                "run `volt node use <version>` and make sure the link directory is on PATH"
+                    .to_string(),
+            )
+        }
+    };
+
+    let reported = verify::version_of(Command::new(&node)).unwrap_or_default();
+    let reported = reported.trim_start_matches('v');
+
+    match current {
+        Some(version) if version == reported => Check::pass(format!(
+            "`node` on PATH ({}) is {}",
+            node.display(),
+            version
+        )),
+        Some(version) => Check::fail(
+            format!(
+                "`node` on PATH ({}) is {}, not the selected {}",
+                node.display(),
+                if reported.is_empty() {
+                    "unknown"
+                } else {
+                    reported
+                },
+                version
+            ),
+            "move the link directory ahead of other node installs on PATH".to_string(),
+        ),
+        None => Check::fail(
+            format!("`node` on PATH ({}) isn't managed by volt", node.display()),
+            "run `volt node use <version>` to select a version".to_string(),
+        ),
+    }
+}
+
+fn check_installed(config: &VoltConfig, version: &str) -> Result<Check> {
+    let bin = get_bin_dir(config, version)?;
+    let reinstall = format!("run `volt node reinstall {version}` to replace it");
+
+    // npm is only reported, a version can work fine without it
+    let npm = if cfg!(windows) { "npm.cmd" } else { "npm" };
+    let npm = verify::version_of(verify::version_command(&bin, npm))
+        .map(|npm| format!("npm {npm}"))
+        .unwrap_or_else(|e| format!("npm unavailable: {e}"));
+
+    Ok(match verify::node_version(&bin, version) {
+        Ok(reported) => Check::pass(format!("node {version}: {reported}, {npm}")),
+        Err(mismatch @ verify::Mismatch::Reported(_)) => Check::fail(
+            format!("node {version}: {mismatch} ({npm})"),
+            format!("the install is corrupt or mislabeled, {reinstall}"),
+        ),
+        Err(mismatch) => Check::fail(format!("node {version}: {mismatch}"), reinstall),
+    })
+}
@@ -0,0 +1,331 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Download release artifacts from the node mirror
+
+use std::{
+    fs::{File, OpenOptions},
+    future::Future,
+    io::Write,
+    path::Path,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use indicatif::{ProgressBar, ProgressStyle};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use rand::Rng;
+use reqwest::{header, Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use super::cancel;
+use crate::cli::VoltConfig;
+
+/// Directory in the data directory that artifacts are downloaded to
+pub const DOWNLOADS_DIR: &str = "downloads";
+
+/// Delay before the first retry, doubled for every retry after it
+const BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// How persistent to be with a slow or flaky mirror
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Times to retry a request after a transient failure
+    pub retries: u32,
+    /// Longest to wait for a response, or for the next chunk of a download
+    pub timeout: Duration,
+}
+
+impl Limits {
+    pub fn new(config: &VoltConfig) -> Self {
+        Self {
+            retries: config.retries(),
+            timeout: config.timeout(),
+        }
+    }
+}
+
+/// What a server sent to identify a version of a file, to ask later whether it changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_response(response: &Response) -> Self {
+        let get = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        Self {
+            etag: get(header::ETAG),
+            last_modified: get(header::LAST_MODIFIED),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Make `request` conditional, so the server answers `304 Not Modified` if nothing changed
+    fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RequestError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+
+    #[error("download timed out after {}s", .0.as_secs())]
+    TimedOut(Duration),
+
+    #[error("the connection closed after {received} of {expected} bytes")]
+    Incomplete { received: u64, expected: u64 },
+
+    #[error("failed to write the download to disk")]
+    Io(#[from] std::io::Error),
+
+    #[error("the download was cancelled")]
+    Cancelled,
+}
+
+/// Whether a failed request is worth retrying
+///
+/// Timeouts, dropped connections and server errors are, anything the server answered
+/// deliberately (e.g. a 404) isn't.
+fn is_transient(error: &RequestError) -> bool {
+    let error = match error {
+        RequestError::Http(error) => error,
+        RequestError::TimedOut(_) | RequestError::Incomplete { .. } => return true,
+        RequestError::Io(_) | RequestError::Cancelled => return false,
+    };
+
+    match error.status() {
+        Some(status) => status.is_server_error(),
+        None => error.is_timeout() || error.is_connect() || error.is_request() || error.is_body(),
+    }
+}
+
+/// Fail with [`RequestError::TimedOut`] if `future` takes longer than `timeout`
+async fn within<T>(
+    timeout: Duration,
+    future: impl Future<Output = reqwest::Result<T>>,
+) -> Result<T, RequestError> {
+    match tokio::time::timeout(timeout, future).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(RequestError::TimedOut(timeout)),
+    }
+}
+
+/// Fail with [`RequestError::Cancelled`] as soon as Ctrl-C is pressed
+async fn unless_cancelled<T>(
+    future: impl Future<Output = Result<T, RequestError>>,
+) -> Result<T, RequestError> {
+    tokio::select! {
+        biased;
+        _ = cancel::cancelled() => Err(RequestError::Cancelled),
+        result = future => result,
+    }
+}
+
+/// Exponential backoff with up to 50% jitter, so parallel downloads don't retry in lockstep
+fn backoff(attempt: u32) -> Duration {
+    let delay = BASE_DELAY * 2u32.saturating_pow(attempt - 1);
+
+    delay + delay.mul_f64(rand::thread_rng().gen_range(0.0..0.5))
+}
+
+/// Run `request` against `url`, retrying transient failures up to `retries` times
+async fn with_retries<T, F, Fut>(retries: u32, url: &str, mut request: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, RequestError>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        match request().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < retries && is_transient(&e) => {
+                attempt += 1;
+                let delay = backoff(attempt);
+                tracing::debug!(
+                    "Request to {} failed ({}), retrying in {:?} ({}/{})",
+                    url,
+                    e,
+                    delay,
+                    attempt,
+                    retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                return Err(e)
+                    .into_diagnostic()
+                    .wrap_err(format!("failed to fetch {url}"))
+            }
+        }
+    }
+}
+
+/// Fetch and parse a JSON document, unless it hasn't changed since it was sent with `validators`
+///
+/// Returns `None` when the server answers `304 Not Modified`, otherwise the document along
+/// with its new validators. Without validators this is a plain request.
+pub async fn json_if_modified<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    validators: &Validators,
+    limits: Limits,
+) -> Result<Option<(T, Validators)>> {
+    with_retries(limits.retries, url, || {
+        within(limits.timeout, async {
+            let response = validators.apply(client.get(url)).send().await?;
+            if response.status() == StatusCode::NOT_MODIFIED && !validators.is_empty() {
+                return Ok(None);
+            }
+
+            let response = response.error_for_status()?;
+            let fresh = Validators::from_response(&response);
+            Ok(Some((response.json().await?, fresh)))
+        })
+    })
+    .await
+}
+
+/// Fetch a small text file, e.g. `SHASUMS256.txt`
+pub async fn text(client: &Client, url: &str, limits: Limits) -> Result<String> {
+    with_retries(limits.retries, url, || {
+        within(limits.timeout, async {
+            client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await
+        })
+    })
+    .await
+}
+
+/// Fetch a small binary file, e.g. `SHASUMS256.txt.sig`
+pub async fn bytes(client: &Client, url: &str, limits: Limits) -> Result<Bytes> {
+    with_retries(limits.retries, url, || {
+        within(limits.timeout, async {
+            client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await
+        })
+    })
+    .await
+}
+
+/// Download `url` into the file at `part`, reporting progress on `pb`
+///
+/// Whatever is already in `part` is kept and the rest requested with a `Range` header, which
+/// retries do too, so an interrupted download picks up where it stopped. Servers that don't
+/// support ranges send the whole file and it's started over. When the server sends a
+/// `Content-Length`, `pb` is turned into a byte-accurate bar and the download only succeeds
+/// once all of it has arrived.
+pub async fn download(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    limits: Limits,
+    pb: &ProgressBar,
+) -> Result<()> {
+    with_retries(limits.retries, url, || {
+        download_once(client, url, part, limits.timeout, pb)
+    })
+    .await
+    .wrap_err(format!("failed to download {url}"))
+}
+
+/// Download the rest of `url` into `part` once, giving up if the mirror goes quiet for
+/// longer than `timeout`
+async fn download_once(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    timeout: Duration,
+    pb: &ProgressBar,
+) -> Result<(), RequestError> {
+    let offset = std::fs::metadata(part).map_or(0, |m| m.len());
+
+    let mut request = client.get(url);
+    if offset > 0 {
+        tracing::debug!("Resuming {} from byte {}", url, offset);
+        request = request.header(header::RANGE, format!("bytes={offset}-"));
+    }
+    let response = unless_cancelled(within(timeout, request.send())).await?;
+
+    // Nothing left after the offset, the download finished but wasn't used yet
+    if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(());
+    }
+
+    let mut response = response.error_for_status()?;
+    let (mut file, start) = if response.status() == StatusCode::PARTIAL_CONTENT {
+        (OpenOptions::new().append(true).open(part)?, offset)
+    } else {
+        (File::create(part)?, 0)
+    };
+
+    let expected = response.content_length().map(|len| start + len);
+    pb.set_position(start);
+    if let Some(total) = expected {
+        pb.set_length(total);
+        pb.set_style(
+            ProgressStyle::default_bar().template(
+                "{msg} [{bar:30.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+            ),
+        );
+    }
+
+    let mut received = start;
+    while let Some(chunk) = unless_cancelled(within(timeout, response.chunk())).await? {
+        file.write_all(&chunk)?;
+        received += chunk.len() as u64;
+        pb.inc(chunk.len() as u64);
+    }
+
+    match expected {
+        Some(expected) if received != expected => {
+            Err(RequestError::Incomplete { received, expected })
+        }
+        _ => Ok(()),
+    }
+}
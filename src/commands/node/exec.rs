@@ -0,0 +1,72 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use std::{env, process::Command};
+
+use async_trait::async_trait;
+use clap::Parser;
+use miette::{IntoDiagnostic, Result, WrapErr};
+
+use super::{get_bin_dir, resolve_installed};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Run a command with a specific node version, without switching to it
+#[derive(Debug, Parser)]
+pub struct NodeExec {
+    /// Version to run the command with
+    version: String,
+
+    /// Command to run, after `--`
+    #[clap(last = true, required = true)]
+    command: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for NodeExec {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let version = resolve_installed(&self.version, &config).await?;
+        if !config.node_dir()?.join(&version).exists() {
+            return Err(VoltError::NodeVersionNotInstalled { version }.into());
+        }
+
+        let bin = get_bin_dir(&config, &version)?;
+
+        let path = env::var_os("PATH").unwrap_or_default();
+        let path = env::join_paths(std::iter::once(bin).chain(env::split_paths(&path)))
+            .into_diagnostic()
+            .wrap_err("failed to build PATH")?;
+
+        let (program, args) = self.command.split_first().unwrap();
+
+        let status = Command::new(program)
+            .args(args)
+            .env("PATH", path)
+            .status()
+            .into_diagnostic()
+            .wrap_err(format!("failed to run `{program}`"))?;
+
+        if !status.success() {
+            // Killed by a signal when there's no code
+            let code = status.code().unwrap_or(1);
+            return Err(VoltError::NodeExecFailed { code }.into());
+        }
+
+        Ok(())
+    }
+}
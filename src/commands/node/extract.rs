@@ -0,0 +1,278 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Unpacking downloaded node releases into the node directory
+
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use indicatif::{ProgressBar, ProgressStyle};
+use miette::{IntoDiagnostic, Result, WrapErr};
+
+use super::cancel;
+#[cfg(target_family = "windows")]
+use crate::core::utils::errors::VoltError;
+
+/// Prefix of the staging directories releases are unpacked into
+const STAGING_PREFIX: &str = ".staging-";
+
+/// Unpack the release `artifact` and move the node folder inside it to `dest`
+///
+/// The archive is unpacked into a staging directory next to `dest` and only renamed into
+/// place once it's complete, so a failure never leaves a half-extracted version behind.
+/// An existing `dest` is replaced, and restored if the swap fails.
+///
+/// The archive is read as it's unpacked, usually straight from the downloaded file, so it's
+/// never held in memory. `pb` becomes a bar that follows the extraction.
+pub fn unpack(
+    archive: impl Read + Seek,
+    artifact: &str,
+    dest: &Path,
+    pb: &ProgressBar,
+) -> Result<()> {
+    let parent = dest
+        .parent()
+        .expect("version directories live in the node directory");
+
+    // Removed when dropped, including when we bail out early
+    let staging = tempfile::Builder::new()
+        .prefix(STAGING_PREFIX)
+        .tempdir_in(parent)
+        .into_diagnostic()
+        .wrap_err(format!(
+            "failed to create a staging directory in {}",
+            parent.display()
+        ))?;
+
+    let from = unpack_into(archive, artifact, staging.path(), pb)?;
+
+    // Move an existing install into the staging dir so it's deleted along with it
+    let previous = staging.path().join("previous");
+    let replacing = dest.exists();
+    if replacing {
+        std::fs::rename(dest, &previous)
+            .into_diagnostic()
+            .wrap_err(format!("failed to move {} aside", dest.display()))?;
+    }
+
+    if let Err(e) = std::fs::rename(&from, dest) {
+        if replacing {
+            let _ = std::fs::rename(&previous, dest);
+        }
+
+        return Err(e).into_diagnostic().wrap_err(format!(
+            "failed to move {} to {}",
+            from.display(),
+            dest.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Switch `pb` to a bar of `len` steps for the extraction
+fn start_bar(pb: &ProgressBar, len: u64) {
+    pb.set_position(0);
+    pb.set_length(len);
+    pb.set_style(ProgressStyle::default_bar().template("{msg} [{bar:30.cyan/blue}] {percent}%"));
+}
+
+/// Unpack the archive into `staging`, returning the folder it extracted to
+///
+/// The format follows the artifact's extension rather than the host, so releases for other
+/// platforms can be unpacked too.
+fn unpack_into(
+    archive: impl Read + Seek,
+    artifact: &str,
+    staging: &Path,
+    pb: &ProgressBar,
+) -> Result<PathBuf> {
+    if let Some(name) = artifact.strip_suffix(".zip") {
+        unpack_zip(archive, artifact, staging, pb)?;
+
+        // The zip extracts to a folder named after itself
+        Ok(staging.join(name))
+    } else if let Some(name) = artifact.strip_suffix(".tar.xz") {
+        unpack_tar_xz(archive, artifact, staging, pb)?;
+
+        // The tarball extracts to a folder named after itself
+        Ok(staging.join(name))
+    } else if let Some(name) = artifact.strip_suffix(".tar.gz") {
+        unpack_tar_gz(archive, artifact, staging, pb)?;
+
+        Ok(staging.join(name))
+    } else {
+        unreachable!("node releases are downloaded as .zip, .tar.xz or .tar.gz")
+    }
+}
+
+fn unpack_zip(
+    archive: impl Read + Seek,
+    artifact: &str,
+    staging: &Path,
+    pb: &ProgressBar,
+) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(archive)
+        .into_diagnostic()
+        .wrap_err(format!("failed to read {artifact}"))?;
+
+    // Ticks once per file, the zip's index tells us how many there are
+    start_bar(pb, archive.len() as u64);
+
+    for index in 0..archive.len() {
+        // The staging directory is removed on the way out
+        cancel::stop_if_cancelled()?;
+
+        let mut file = archive
+            .by_index(index)
+            .into_diagnostic()
+            .wrap_err(format!("failed to read {artifact}"))?;
+
+        // Skip entries that would land outside the staging directory
+        let path = match file.enclosed_name() {
+            Some(path) => staging.join(path),
+            None => continue,
+        };
+
+        let result = if file.is_dir() {
+            std::fs::create_dir_all(&path)
+        } else {
+            path.parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::File::create(&path))
+                .and_then(|mut out| std::io::copy(&mut file, &mut out))
+                .map(|_| ())
+        };
+
+        result
+            .into_diagnostic()
+            .wrap_err(format!("failed to extract {} from {artifact}", file.name()))?;
+
+        pb.inc(1);
+    }
+
+    Ok(())
+}
+
+/// The size of `archive`, leaving it at the start
+fn archive_len(archive: &mut impl Seek, artifact: &str) -> Result<u64> {
+    archive
+        .seek(SeekFrom::End(0))
+        .and_then(|len| archive.seek(SeekFrom::Start(0)).map(|_| len))
+        .into_diagnostic()
+        .wrap_err(format!("failed to read {artifact}"))
+}
+
+#[cfg(target_family = "unix")]
+fn unpack_tar_xz(
+    mut archive: impl Read + Seek,
+    artifact: &str,
+    staging: &Path,
+    pb: &ProgressBar,
+) -> Result<()> {
+    // The number of entries isn't known up front, so follow how much of the download is read
+    start_bar(pb, archive_len(&mut archive, artifact)?);
+
+    // Decompress as the tarball is read, so the decompressed tarball is never held in full
+    let tarball = lzma::LzmaReader::new_decompressor(pb.wrap_read(archive))
+        .into_diagnostic()
+        .wrap_err(format!("failed to decompress {artifact}"))?;
+
+    unpack_tar(tarball, artifact, staging)
+}
+
+fn unpack_tar_gz(
+    mut archive: impl Read + Seek,
+    artifact: &str,
+    staging: &Path,
+    pb: &ProgressBar,
+) -> Result<()> {
+    start_bar(pb, archive_len(&mut archive, artifact)?);
+
+    unpack_tar(
+        flate2::read::GzDecoder::new(pb.wrap_read(archive)),
+        artifact,
+        staging,
+    )
+}
+
+/// Unpack the entries of a decompressed tarball into `staging`
+fn unpack_tar(tarball: impl Read, artifact: &str, staging: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(tarball);
+    let entries = archive
+        .entries()
+        .into_diagnostic()
+        .wrap_err(format!("failed to read {artifact}"))?;
+
+    for entry in entries {
+        // The staging directory is removed on the way out
+        cancel::stop_if_cancelled()?;
+
+        entry
+            .and_then(|mut entry| entry.unpack_in(staging))
+            .into_diagnostic()
+            .wrap_err(format!("failed to extract {artifact}"))?;
+    }
+
+    Ok(())
+}
+
+/// There's no xz decoder on windows, so `.tar.xz` releases can only be unpacked elsewhere
+#[cfg(target_family = "windows")]
+fn unpack_tar_xz(_: impl Read, artifact: &str, _: &Path, _: &ProgressBar) -> Result<()> {
+    Err(VoltError::NodeUnsupportedArchive {
+        artifact: artifact.to_string(),
+    }
+    .into())
+}
+
+#[cfg(all(test, target_family = "unix"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_tarball_leaves_nothing_behind() {
+        let mut builder = tar::Builder::new(Vec::new());
+        let node = vec![0u8; 64 * 1024];
+        let mut header = tar::Header::new_gnu();
+        header.set_size(node.len() as u64);
+        header.set_mode(0o755);
+        builder
+            .append_data(&mut header, "node-v1.0.0-linux-x64/bin/node", &node[..])
+            .unwrap();
+        let mut tarball = builder.into_inner().unwrap();
+
+        // Cut off halfway through the file so extraction starts, then fails
+        tarball.truncate(tarball.len() / 2);
+        let content = lzma::compress(&tarball, 6).unwrap();
+
+        let node_dir = tempfile::tempdir().unwrap();
+        let dest = node_dir.path().join("1.0.0");
+
+        let result = unpack(
+            std::io::Cursor::new(content),
+            "node-v1.0.0-linux-x64.tar.xz",
+            &dest,
+            &ProgressBar::hidden(),
+        );
+
+        assert!(result.is_err());
+        assert!(!dest.exists());
+        assert_eq!(std::fs::read_dir(node_dir.path()).unwrap().count(), 0);
+    }
+}
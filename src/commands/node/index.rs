@@ -0,0 +1,249 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Fetching and caching the mirror's `index.json`
+
+use std::{
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    download::{self, Validators},
+    mirror, NodeVersion,
+};
+use crate::{cli::VoltConfig, core::utils::errors::VoltError};
+
+/// Name of the cached index, stored in the data directory
+const INDEX_CACHE: &str = "node-index.json";
+
+#[derive(Serialize, Deserialize)]
+struct CachedIndex {
+    /// Mirror the index was fetched from, a cache for another mirror is never used
+    mirror: String,
+    /// Seconds since the unix epoch when the index was fetched
+    fetched_at: u64,
+    /// Sent back when the cache expires, so an unchanged index isn't downloaded again
+    #[serde(flatten)]
+    validators: Validators,
+    index: serde_json::Value,
+}
+
+impl CachedIndex {
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        now().saturating_sub(self.fetched_at) < ttl.as_secs()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Download the raw `index.json` from the mirror, or `None` if it's unchanged since
+/// `validators`
+async fn fetch(
+    client: &reqwest::Client,
+    mirror: &str,
+    validators: &Validators,
+    limits: download::Limits,
+) -> Result<Option<(serde_json::Value, Validators)>> {
+    download::json_if_modified(
+        client,
+        &format!("{}/index.json", mirror),
+        validators,
+        limits,
+    )
+    .await
+}
+
+fn parse(index: serde_json::Value) -> Result<Vec<NodeVersion>> {
+    serde_json::from_value(index)
+        .into_diagnostic()
+        .wrap_err("failed to parse the node release index")
+}
+
+/// Read the cached index for `mirror`, if there is one
+fn read_cache(path: &Path, mirror: &str) -> Option<CachedIndex> {
+    let cached: CachedIndex = serde_json::from_slice(&std::fs::read(path).ok()?).ok()?;
+
+    (cached.mirror == mirror).then(|| cached)
+}
+
+/// Best-effort write of the cache, failing to cache shouldn't fail the command
+fn write_cache(path: &Path, cached: &CachedIndex) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(path, serde_json::to_vec(cached)?));
+
+    if let Err(e) = result {
+        tracing::debug!(
+            "Failed to cache the node release index at {:?}: {}",
+            path,
+            e
+        );
+    }
+}
+
+/// Released versions on the configured mirror
+///
+/// The index is reused from the cache if it's younger than `--index-ttl` (unless `--refresh`
+/// is set). Otherwise it's fetched from the first mirror that works, and a stale cache is used
+/// as a fallback when none of them can be reached. The cache belongs to the primary mirror.
+///
+/// Once the cache expires, the primary mirror is asked whether the index changed since, using
+/// the `ETag`/`Last-Modified` it sent with it. If it hasn't, the cache is just marked fresh
+/// again. `--refresh` always downloads the whole index.
+///
+/// With `--offline` the cache is used however old it is, and it's an error not to have one.
+pub async fn node_versions(
+    client: &reqwest::Client,
+    config: &VoltConfig,
+) -> Result<Vec<NodeVersion>> {
+    let mirror = &config.node_mirror()?;
+    let path = config.volt_dir()?.join(INDEX_CACHE);
+    let cached = read_cache(&path, mirror);
+
+    if config.offline() {
+        tracing::debug!(
+            "Offline, using the cached node release index from {:?}",
+            path
+        );
+        return match cached {
+            Some(cached) => parse(cached.index),
+            None => Err(VoltError::NodeOfflineNoIndex.into()),
+        };
+    }
+
+    if let Some(cached) = &cached {
+        if !config.refresh() && cached.is_fresh(config.index_ttl()) {
+            tracing::debug!("Using cached node release index from {:?}", path);
+            return parse(cached.index.clone());
+        }
+    }
+
+    let mirrors = config.node_mirrors()?;
+    let limits = download::Limits::new(config);
+    let validators = match &cached {
+        Some(cached) if !config.refresh() => cached.validators.clone(),
+        _ => Validators::default(),
+    };
+    let unconditional = Validators::default();
+    let fetched = mirror::with_fallback(&mirrors, |m| {
+        // The validators came from the primary mirror and mean nothing to the others
+        let validators = if m == mirror {
+            &validators
+        } else {
+            &unconditional
+        };
+        fetch(client, m, validators, limits)
+    })
+    .await;
+
+    let (index, validators) = match fetched {
+        Ok((Some(fetched), used)) => {
+            tracing::debug!("Fetched the node release index from {}", used);
+            fetched
+        }
+        Ok((None, used)) => {
+            // Only the cache's own validators can get a `304 Not Modified`
+            let mut cached = cached.expect("conditional requests need a cached index");
+            tracing::debug!("The node release index on {} hasn't changed", used);
+
+            cached.fetched_at = now();
+            write_cache(&path, &cached);
+            return parse(cached.index);
+        }
+        Err(e) => match cached {
+            Some(cached) => {
+                eprintln!(
+                    "{} {}, using the cached release index",
+                    "warning:".yellow(),
+                    e
+                );
+                return parse(cached.index);
+            }
+            None => return Err(e),
+        },
+    };
+
+    let versions = parse(index.clone())?;
+
+    write_cache(
+        &path,
+        &CachedIndex {
+            mirror: mirror.to_string(),
+            fetched_at: now(),
+            validators,
+            index,
+        },
+    );
+
+    Ok(versions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn unreachable_mirror_is_an_error() {
+        // Nothing listens on the discard port, so the connection is refused straight away
+        let limits = download::Limits {
+            retries: 0,
+            timeout: Duration::from_secs(5),
+        };
+        let result = fetch(
+            &reqwest::Client::new(),
+            "http://127.0.0.1:9",
+            &Validators::default(),
+            limits,
+        )
+        .await;
+
+        let err = result.expect_err("fetching from an unreachable mirror should fail");
+        assert!(err.to_string().contains("http://127.0.0.1:9/index.json"));
+    }
+
+    #[test]
+    fn cache_is_scoped_to_its_mirror_and_expires() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(INDEX_CACHE);
+
+        write_cache(
+            &path,
+            &CachedIndex {
+                mirror: "https://nodejs.org/dist".to_string(),
+                fetched_at: now() - 60,
+                validators: Validators::default(),
+                index: serde_json::json!([]),
+            },
+        );
+
+        assert!(read_cache(&path, "https://example.com/node").is_none());
+
+        let cached = read_cache(&path, "https://nodejs.org/dist").unwrap();
+        assert!(cached.is_fresh(Duration::from_secs(3600)));
+        assert!(!cached.is_fresh(Duration::from_secs(30)));
+    }
+}
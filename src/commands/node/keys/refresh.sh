@@ -0,0 +1,23 @@
+#!/bin/sh
+# Regenerate release-keys.asc from https://github.com/nodejs/release-keys
+#
+# usage: ./refresh.sh
+set -eu
+
+cd "$(dirname "$0")"
+
+base="https://raw.githubusercontent.com/nodejs/release-keys/HEAD"
+out="release-keys.asc"
+
+{
+    echo "# Public keys of the Node.js releasers, used by \`volt node install --verify-signatures\`."
+    echo "# Generated by \`refresh.sh\` - see README.md in this directory. Do not edit by hand."
+    curl -fsSL "$base/keys.list" | while read -r fingerprint; do
+        [ -n "$fingerprint" ] || continue
+        echo "# $fingerprint"
+        curl -fsSL "$base/keys/$fingerprint.asc"
+    done
+} > "$out.tmp"
+
+mv "$out.tmp" "$out"
+echo "Wrote $(grep -c 'BEGIN PGP PUBLIC KEY BLOCK' "$out") keys to $out"
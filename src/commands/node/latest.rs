@@ -0,0 +1,93 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Query the newest releases on the mirror
+
+use async_trait::async_trait;
+use clap::Parser;
+use miette::{IntoDiagnostic, Result};
+use serde::Serialize;
+
+use super::{fetch_node_versions, latest_lts, NodeVersion};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Print the newest version of node available on the mirror
+#[derive(Debug, Parser)]
+pub struct NodeLatest {
+    /// Print the release as JSON, e.g. `{ "version": "20.10.0", "lts": null }`
+    #[clap(long)]
+    json: bool,
+}
+
+/// Print the newest LTS version of node available on the mirror
+#[derive(Debug, Parser)]
+pub struct NodeLatestLts {
+    /// Print the release as JSON, e.g. `{ "version": "18.19.0", "lts": "Hydrogen" }`
+    #[clap(long)]
+    json: bool,
+}
+
+/// A release, as printed by `--json`
+#[derive(Serialize)]
+struct Release<'a> {
+    version: String,
+    lts: Option<&'a str>,
+}
+
+fn print(release: &NodeVersion, json: bool) -> Result<()> {
+    if json {
+        let release = Release {
+            version: release.version.to_string(),
+            lts: release.lts.as_deref(),
+        };
+        println!("{}", serde_json::to_string(&release).into_diagnostic()?);
+    } else {
+        println!("{}", release.version);
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl VoltCommand for NodeLatest {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let node_versions = fetch_node_versions(&config, &config.http_client()?).await?;
+
+        let latest = node_versions
+            .iter()
+            .max_by(|a, b| a.version.cmp(&b.version))
+            .ok_or_else(|| VoltError::NodeNoVersionSatisfies {
+                range: "*".to_string(),
+            })?;
+
+        print(latest, self.json)
+    }
+}
+
+#[async_trait]
+impl VoltCommand for NodeLatestLts {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let node_versions = fetch_node_versions(&config, &config.http_client()?).await?;
+
+        let version = latest_lts(&node_versions, None)?;
+        let latest = node_versions.iter().find(|n| n.version == version).unwrap();
+
+        print(latest, self.json)
+    }
+}
@@ -0,0 +1,109 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Installing node from release tarballs that are already on disk, for air-gapped machines
+
+use std::path::{Path, PathBuf};
+
+use indicatif::ProgressBar;
+use miette::{IntoDiagnostic, Result, WrapErr};
+use node_semver::Version;
+
+use super::{checksum, extract, meta, Target, ARCH, ARTIFACT_EXTS, PLATFORM};
+use crate::core::utils::errors::VoltError;
+
+/// A release artifact on disk, e.g. `./node-v18.19.0-linux-x64.tar.xz` or `.tar.gz`
+#[derive(Debug)]
+pub struct LocalArtifact {
+    pub path: PathBuf,
+    pub version: Version,
+}
+
+/// Interpret `arg` as a local artifact if it's a `file://` URL or a path to an existing file
+///
+/// Fails if the file isn't a release for this platform, judging by its name.
+pub fn artifact(arg: &str) -> Result<Option<LocalArtifact>> {
+    let path = if arg.starts_with("file://") {
+        reqwest::Url::parse(arg)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| VoltError::NodeInvalidVersion {
+                version: arg.to_string(),
+            })?
+    } else if Path::new(arg).is_file() {
+        PathBuf::from(arg)
+    } else {
+        return Ok(None);
+    };
+
+    let suffixes = ARTIFACT_EXTS
+        .iter()
+        .map(|ext| format!("-{PLATFORM}-{ARCH}.{ext}"))
+        .collect::<Vec<_>>();
+    let version = path
+        .file_name()
+        .and_then(|name| name.to_str()?.strip_prefix("node-v"))
+        .and_then(|name| suffixes.iter().find_map(|s| name.strip_suffix(s)))
+        .and_then(|version| version.parse().ok())
+        .ok_or_else(|| VoltError::NodeInvalidArtifact {
+            file: path.display().to_string(),
+            expected: format!("node-v<version>{}", suffixes[0]),
+        })?;
+
+    Ok(Some(LocalArtifact { path, version }))
+}
+
+/// Unpack `artifact` into `node_dir`
+///
+/// The archive is checked against a `SHASUMS256.txt` next to it when there is one, as
+/// there's no mirror to fetch it from.
+pub fn install(artifact: &LocalArtifact, node_dir: &Path, skip_checksum: bool) -> Result<()> {
+    let name = artifact.path.file_name().unwrap().to_string_lossy();
+
+    let read_error = |e| VoltError::ReadFileError {
+        source: e,
+        name: artifact.path.display().to_string(),
+    };
+    let sha256 = checksum::sha256_file(&artifact.path).map_err(read_error)?;
+
+    let shasums_path = artifact.path.with_file_name(checksum::SHASUMS_FILE);
+    if !skip_checksum && shasums_path.exists() {
+        let shasums = std::fs::read_to_string(&shasums_path)
+            .into_diagnostic()
+            .wrap_err(format!("failed to read {}", shasums_path.display()))?;
+        checksum::verify(&shasums, &name, &sha256)?;
+    } else {
+        tracing::debug!(
+            "Not verifying {}, no {} next to it",
+            name,
+            checksum::SHASUMS_FILE
+        );
+    }
+
+    let dest = node_dir.join(artifact.version.to_string());
+    let archive = std::fs::File::open(&artifact.path).map_err(read_error)?;
+    extract::unpack(archive, &name, &dest, &ProgressBar::hidden())?;
+
+    let url = std::fs::canonicalize(&artifact.path)
+        .ok()
+        .and_then(|path| reqwest::Url::from_file_path(path).ok())
+        .map_or_else(|| artifact.path.display().to_string(), String::from);
+    let node = Target::NATIVE.node_binary(&dest);
+    let version = artifact.version.to_string();
+    meta::write(&dest, &meta::InstallMeta::new(&version, url, sha256, &node));
+
+    Ok(())
+}
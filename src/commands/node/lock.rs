@@ -0,0 +1,60 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Keeping concurrent volt processes from changing the data directory at the same time
+
+use std::fs::File;
+
+use fs2::FileExt;
+use miette::{IntoDiagnostic, Result, WrapErr};
+
+use crate::{cli::VoltConfig, core::utils::errors::VoltError};
+
+/// Name of the lock file, stored in the data directory
+const LOCK_FILE: &str = ".lock";
+
+/// Exclusive hold on the data directory, released when dropped
+///
+/// The lock is advisory and tied to the open file, so it's also released if volt is killed.
+pub struct DataDirLock {
+    _file: File,
+}
+
+/// Lock the data directory, waiting for any other volt process holding it to finish
+pub async fn acquire(config: &VoltConfig) -> Result<DataDirLock> {
+    let dir = config.volt_dir()?;
+    std::fs::create_dir_all(&dir).map_err(VoltError::CreateDirError)?;
+
+    let path = dir.join(LOCK_FILE);
+    let file = File::create(&path)
+        .into_diagnostic()
+        .wrap_err(format!("failed to open {}", path.display()))?;
+
+    if file.try_lock_exclusive().is_ok() {
+        return Ok(DataDirLock { _file: file });
+    }
+
+    eprintln!("Waiting for another volt process to finish...");
+
+    // Blocks until the other process lets go, so keep it off the async workers
+    let file = tokio::task::spawn_blocking(move || file.lock_exclusive().map(|_| file))
+        .await
+        .into_diagnostic()?
+        .into_diagnostic()
+        .wrap_err(format!("failed to lock {}", path.display()))?;
+
+    Ok(DataDirLock { _file: file })
+}
@@ -0,0 +1,84 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Provenance recorded in each version directory when it's installed
+
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::checksum;
+
+/// Name of the metadata file inside a version directory
+///
+/// Whether a version is installed only depends on its directory, never on this file.
+pub const META_FILE: &str = ".volt-meta.json";
+
+/// How a version was installed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstallMeta {
+    pub version: String,
+    /// Where the release came from, a `file://` URL for local artifacts
+    pub url: String,
+    /// sha256 of the downloaded artifact
+    pub sha256: String,
+    /// sha256 of the unpacked node binary, so `volt node verify` can check it offline
+    pub node_sha256: Option<String>,
+    /// Seconds since the unix epoch when the version was installed
+    pub installed_at: u64,
+}
+
+impl InstallMeta {
+    /// Metadata for `version` installed from the artifact at `url`, whose sha256 is `sha256`
+    pub fn new(version: &str, url: String, sha256: String, node_binary: &Path) -> Self {
+        Self {
+            version: version.to_string(),
+            url,
+            sha256,
+            node_sha256: hash_file(node_binary),
+            installed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Hex encoded sha256 of the file at `path`, if it can be read
+pub fn hash_file(path: &Path) -> Option<String> {
+    checksum::sha256_file(path).ok()
+}
+
+/// The metadata recorded for the version in `version_dir`, versions installed by older
+/// releases of volt don't have any
+pub fn read(version_dir: &Path) -> Option<InstallMeta> {
+    serde_json::from_slice(&std::fs::read(version_dir.join(META_FILE)).ok()?).ok()
+}
+
+/// Best-effort write of the metadata, the install itself already succeeded
+pub fn write(version_dir: &Path, meta: &InstallMeta) {
+    let path = version_dir.join(META_FILE);
+    let result = serde_json::to_vec_pretty(meta)
+        .map_err(std::io::Error::from)
+        .and_then(|contents| std::fs::write(&path, contents));
+
+    if let Err(e) = result {
+        tracing::debug!("Failed to write {:?}: {}", path, e);
+    }
+}
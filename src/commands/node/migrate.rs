@@ -0,0 +1,245 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Moving installed versions out of an old data directory
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+
+use super::{alias, versions_in, CROSS_DIR};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Move installed versions from an old data directory to the current one
+///
+/// Use this after changing `VOLT_HOME`, `--data-dir` or `--xdg`. Versions that are already
+/// installed in the current directory are left where they are, nothing is overwritten. Unless
+/// a version is already in use in the current directory, the old one is switched to again so
+/// its binaries link to the new location.
+#[derive(Debug, Parser)]
+pub struct NodeMigrate {
+    /// The old data directory, the one holding `node`
+    from: PathBuf,
+}
+
+#[async_trait]
+impl VoltCommand for NodeMigrate {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let old_node_dir = self.from.join("node");
+        let node_dir = config.node_dir()?;
+
+        if same_dir(&old_node_dir, &node_dir) {
+            return Err(VoltError::NodeMigrateToItself {
+                dir: self.from.display().to_string(),
+            }
+            .into());
+        }
+        if versions_in(&old_node_dir).is_empty() && !old_node_dir.join(CROSS_DIR).exists() {
+            return Err(VoltError::NodeNothingToMigrate {
+                dir: self.from.display().to_string(),
+            }
+            .into());
+        }
+
+        let previous = old_current(&self.from);
+
+        let mut moves = Moves::default();
+        moves.versions(&old_node_dir, &node_dir, None);
+
+        // Cross-installs move along with the native ones, platform by platform
+        if let Ok(entries) = std::fs::read_dir(old_node_dir.join(CROSS_DIR)) {
+            for entry in entries.flatten() {
+                let platform = entry.file_name().to_string_lossy().to_string();
+                let dest = node_dir.join(CROSS_DIR).join(&platform);
+                moves.versions(&entry.path(), &dest, Some(&platform));
+                let _ = std::fs::remove_dir(entry.path());
+            }
+            let _ = std::fs::remove_dir(old_node_dir.join(CROSS_DIR));
+        }
+
+        migrate_aliases(&self.from, &config.volt_dir()?)?;
+
+        // Keep the version selected in the new directory, if there's one
+        let selected = std::fs::symlink_metadata(config.current_marker()?).is_ok();
+        if let Some(version) = previous.filter(|v| moves.moved.contains(v) && !selected) {
+            relink(&config, &old_node_dir, &version).await?;
+            println!("Switched to node {version} in its new location");
+        }
+
+        // Only the dangling `current` link is left if everything moved, so this may fail
+        let _ = std::fs::remove_file(old_node_dir.join("current"));
+        let _ = std::fs::remove_dir(&old_node_dir);
+
+        println!(
+            "Moved {} from {} to {}",
+            count(moves.moved.len()),
+            old_node_dir.display(),
+            node_dir.display()
+        );
+        if !moves.skipped.is_empty() {
+            println!(
+                "Skipped {}, already installed in {}",
+                moves.skipped.join(", "),
+                node_dir.display()
+            );
+        }
+
+        if !moves.failed.is_empty() {
+            return Err(VoltError::NodeVersionsNotMigrated {
+                versions: moves.failed.join(", "),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Versions by what happened to them, cross-installs are named with their platform
+#[derive(Default)]
+struct Moves {
+    moved: Vec<String>,
+    /// Already installed in the new directory
+    skipped: Vec<String>,
+    failed: Vec<String>,
+}
+
+impl Moves {
+    /// Move the versions installed in `from` to `to`, never replacing one that's there
+    fn versions(&mut self, from: &Path, to: &Path, platform: Option<&str>) {
+        let versions = versions_in(from);
+        if !versions.is_empty() {
+            if let Err(e) = std::fs::create_dir_all(to) {
+                eprintln!(
+                    "{} couldn't create {}: {e}",
+                    "warning:".yellow(),
+                    to.display()
+                );
+            }
+        }
+
+        for version in versions.iter().map(|v| v.to_string()) {
+            let name = match platform {
+                Some(platform) => format!("{version} ({platform})"),
+                None => version.clone(),
+            };
+
+            let dest = to.join(&version);
+            if dest.exists() {
+                self.skipped.push(name);
+                continue;
+            }
+
+            // A rename can't cross filesystems, those are left for the user to move
+            match std::fs::rename(from.join(&version), &dest) {
+                Ok(()) => self.moved.push(name),
+                Err(e) => {
+                    eprintln!(
+                        "{} couldn't move node {name} to {}: {e}",
+                        "warning:".yellow(),
+                        dest.display()
+                    );
+                    self.failed.push(name);
+                }
+            }
+        }
+    }
+}
+
+fn count(n: usize) -> String {
+    match n {
+        1 => "1 version".to_string(),
+        n => format!("{n} versions"),
+    }
+}
+
+/// Whether `a` and `b` are the same directory, even when named differently
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// The version that was in use in the old data directory
+#[cfg(unix)]
+fn old_current(volt_dir: &Path) -> Option<String> {
+    // `current` links to `<version>/bin`
+    let bin = std::fs::read_link(volt_dir.join("node").join("current")).ok()?;
+
+    Some(bin.parent()?.file_name()?.to_str()?.to_string())
+}
+
+#[cfg(windows)]
+fn old_current(volt_dir: &Path) -> Option<String> {
+    let version = std::fs::read_to_string(volt_dir.join("current")).ok()?;
+
+    Some(version.trim().to_string())
+}
+
+/// Point `current` and the linked binaries at `version`'s new location
+async fn relink(config: &VoltConfig, old_node_dir: &Path, version: &str) -> Result<()> {
+    // The old links point into the old directory, so they'd count as someone else's files
+    #[cfg(unix)]
+    if let Ok(entries) = std::fs::read_dir(config.bin_dir()?) {
+        for entry in entries.flatten() {
+            let points_at_old = std::fs::read_link(entry.path())
+                .map_or(false, |target| target.starts_with(old_node_dir));
+            if points_at_old {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    let switched = super::select(config, version, false)?;
+    for (link, e) in &switched.failed {
+        eprintln!(
+            "{} couldn't link {}: {e}",
+            "warning:".yellow(),
+            link.display()
+        );
+    }
+
+    if switched.path_updated {
+        println!(
+            "Added {} to PATH, restart your terminal to use it",
+            config.bin_dir()?.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Bring the alias file along, unless the new directory has its own
+fn migrate_aliases(from: &Path, volt_dir: &Path) -> Result<()> {
+    let old = from.join(alias::ALIASES_FILE);
+    let new = volt_dir.join(alias::ALIASES_FILE);
+
+    if old.exists() && !new.exists() {
+        std::fs::rename(&old, &new).map_err(|e| VoltError::WriteFileError {
+            source: e,
+            name: new.display().to_string(),
+        })?;
+    }
+
+    Ok(())
+}
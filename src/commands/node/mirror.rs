@@ -0,0 +1,84 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Choosing between the configured node mirrors
+
+use std::{future::Future, time::Duration};
+
+use miette::Result;
+use reqwest::Client;
+
+use crate::cli::VoltConfig;
+
+/// The configured mirrors, starting from the first one that responds
+///
+/// Mirrors that didn't respond are moved to the back, so they're still tried if the others
+/// fail later on. A single mirror isn't probed, there's nothing to choose between, and
+/// neither are any with `--offline`.
+pub async fn ordered(client: &Client, config: &VoltConfig) -> Result<Vec<String>> {
+    let mut mirrors = config.node_mirrors()?;
+    if mirrors.len() < 2 || config.offline() {
+        return Ok(mirrors);
+    }
+
+    for i in 0..mirrors.len() {
+        if probe(client, &mirrors[i], config.timeout()).await {
+            tracing::debug!("Using mirror {}", mirrors[i]);
+            mirrors.rotate_left(i);
+            break;
+        }
+
+        tracing::debug!("Mirror {} didn't respond", mirrors[i]);
+    }
+
+    Ok(mirrors)
+}
+
+/// Whether the mirror answers a `HEAD` request for its `index.json` in time
+async fn probe(client: &Client, mirror: &str, timeout: Duration) -> bool {
+    let request = client.head(format!("{mirror}/index.json")).send();
+
+    matches!(
+        tokio::time::timeout(timeout, request).await,
+        Ok(Ok(response)) if response.status().is_success()
+    )
+}
+
+/// Run `request` against each mirror in turn, returning the first success and its mirror
+///
+/// If every mirror fails, the error from the last one is returned.
+pub async fn with_fallback<'m, T, F, Fut>(
+    mirrors: &'m [String],
+    mut request: F,
+) -> Result<(T, &'m str)>
+where
+    F: FnMut(&'m str) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut error = None;
+
+    for mirror in mirrors {
+        match request(mirror).await {
+            Ok(value) => return Ok((value, mirror)),
+            Err(e) => {
+                tracing::debug!("Request to mirror {} failed: {}", mirror, e);
+                error = Some(e);
+            }
+        }
+    }
+
+    Err(error.expect("there's always at least one mirror"))
+}
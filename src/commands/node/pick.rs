@@ -0,0 +1,84 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Picking the versions to install from the mirror's releases, for `volt node install -i`
+
+use std::borrow::Cow;
+
+use colored::Colorize;
+use dialoguer::console::Term;
+use miette::{IntoDiagnostic, Result};
+
+use super::NodeVersion;
+use crate::core::{prompt::prompts::MultiSelect, utils::errors::VoltError};
+
+/// Releases shown at once, the rest are scrolled to
+const PAGE: usize = 15;
+
+/// Let the user check any of `releases` that have a build named `key`
+///
+/// Releases are listed newest first with the major version heading each line, and LTS
+/// releases are highlighted. Returns nothing if the prompt was cancelled.
+pub fn versions(mut releases: Vec<NodeVersion>, key: &str) -> Result<Vec<String>> {
+    if !Term::stderr().is_term() {
+        return Err(VoltError::NodeNotInteractive.into());
+    }
+
+    releases.retain(|n| n.files.iter().any(|file| file == key));
+    releases.sort_by(|a, b| b.version.cmp(&a.version));
+
+    let items = labels(&releases).into_iter().map(Cow::Owned).collect();
+    let picked = MultiSelect {
+        message: "Versions to install (space to select, enter to confirm)".into(),
+        max_length: Some(PAGE),
+        items,
+    }
+    .run()
+    .into_diagnostic()?;
+
+    Ok(picked
+        .unwrap_or_default()
+        .into_iter()
+        .map(|i| releases[i].version.to_string())
+        .collect())
+}
+
+/// A line per release, the major version only on the newest release of each line
+fn labels(releases: &[NodeVersion]) -> Vec<String> {
+    let mut previous = None;
+
+    releases
+        .iter()
+        .map(|n| {
+            let major = n.version.major;
+            let heading = if previous == Some(major) {
+                String::new()
+            } else {
+                format!("v{major}")
+            };
+            previous = Some(major);
+
+            match &n.lts {
+                Some(codename) => format!(
+                    "{heading:4} {:10} {}",
+                    n.version.to_string().green(),
+                    format!("LTS: {codename}").green()
+                ),
+                None => format!("{heading:4} {}", n.version),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,73 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use async_trait::async_trait;
+use clap::{ArgEnum, Parser};
+use miette::Result;
+
+use super::{resolve_installed, version_file};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ArgEnum)]
+enum PinFormat {
+    /// `.nvmrc`, read by nvm and fnm
+    Nvmrc,
+    /// `.node-version`, read by nodenv and fnm
+    NodeVersion,
+}
+
+impl PinFormat {
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Nvmrc => version_file::VERSION_FILES[0],
+            Self::NodeVersion => version_file::VERSION_FILES[1],
+        }
+    }
+}
+
+/// Pin the project in the current directory to a node version
+///
+/// Ranges and aliases are resolved to the installed version they stand for, so the file
+/// always holds an exact version. `volt node use` without a version then picks it up.
+#[derive(Debug, Parser)]
+pub struct NodePin {
+    /// Version, range or alias to pin
+    version: String,
+
+    /// Which version file to write
+    #[clap(long, arg_enum, default_value = "nvmrc")]
+    format: PinFormat,
+}
+
+#[async_trait]
+impl VoltCommand for NodePin {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let version = resolve_installed(&self.version, &config).await?;
+        if !config.node_dir()?.join(&version).exists() {
+            return Err(VoltError::NodeVersionNotInstalled { version }.into());
+        }
+
+        let path = config.cwd()?.join(self.format.file_name());
+        version_file::write(&path, &version)?;
+
+        println!("Pinned node {version} in {}", path.display());
+
+        Ok(())
+    }
+}
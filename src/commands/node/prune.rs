@@ -0,0 +1,110 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use indicatif::HumanBytes;
+use miette::Result;
+
+use super::{
+    confirm, current_version, dir_size, fetch_node_versions, installed_versions, remove_version,
+};
+use crate::cli::{VoltCommand, VoltConfig};
+
+/// Remove every installed version of node except the active one
+#[derive(Debug, Parser)]
+pub struct NodePrune {
+    /// Also keep the newest installed LTS release
+    #[clap(long)]
+    keep_lts: bool,
+
+    /// Only print what would be removed
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Don't ask for confirmation
+    #[clap(short, long)]
+    yes: bool,
+}
+
+#[async_trait]
+impl VoltCommand for NodePrune {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let node_dir = config.node_dir()?;
+        let installed = installed_versions(&config)?;
+
+        let mut keep = current_version(&config)?.into_iter().collect::<Vec<_>>();
+
+        if self.keep_lts {
+            let lts = fetch_node_versions(&config, &config.http_client()?)
+                .await?
+                .into_iter()
+                .filter(|n| n.lts.is_some())
+                .map(|n| n.version)
+                .collect::<Vec<_>>();
+
+            if let Some(version) = installed.iter().rev().find(|v| lts.contains(v)) {
+                keep.push(version.to_string());
+            }
+        }
+
+        let link_dir = config.bin_dir()?;
+
+        let mut targets = vec![];
+
+        for version in installed.iter().map(|v| v.to_string()) {
+            if keep.contains(&version) {
+                println!("Keeping {}", version.truecolor(0, 255, 0));
+            } else {
+                targets.push(version);
+            }
+        }
+
+        if !self.dry_run && !targets.is_empty() {
+            let message = format!("Remove node {}?", targets.join(", "));
+            if !confirm(&message, self.yes)? {
+                return Ok(());
+            }
+        }
+
+        let mut freed = 0;
+
+        for version in &targets {
+            let size = dir_size(&node_dir.join(version));
+
+            if self.dry_run {
+                println!("Would remove {} ({})", version, HumanBytes(size));
+            } else {
+                remove_version(&config, version, &link_dir)?;
+                println!("Removed {} ({})", version, HumanBytes(size));
+            }
+
+            freed += size;
+        }
+
+        if self.dry_run {
+            println!(
+                "Would free {}",
+                HumanBytes(freed).to_string().bright_green()
+            );
+        } else {
+            println!("Freed {}", HumanBytes(freed).to_string().bright_green());
+        }
+
+        Ok(())
+    }
+}
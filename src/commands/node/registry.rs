@@ -0,0 +1,101 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Pointing the npm bundled with a node version at a registry
+
+use std::path::{Path, PathBuf};
+
+use miette::Result;
+
+use crate::core::utils::errors::VoltError;
+
+/// Where the npm bundled with the node in `version_dir` is installed
+fn npm_dir(version_dir: &Path) -> PathBuf {
+    // Windows releases keep `node_modules` at the root of the version directory
+    if cfg!(windows) {
+        version_dir.join("node_modules").join("npm")
+    } else {
+        version_dir.join("lib").join("node_modules").join("npm")
+    }
+}
+
+/// Fail unless `registry` is a URL, checked before switching so a typo switches nothing
+pub fn validate(registry: &str) -> Result<()> {
+    reqwest::Url::parse(registry).map_err(|e| VoltError::InvalidRegistryUrl {
+        url: registry.to_string(),
+        reason: e.to_string(),
+    })?;
+
+    Ok(())
+}
+
+/// Set `registry=` in the builtin `npmrc` of the version's npm
+///
+/// npm reads its builtin config for every project and user, but below `~/.npmrc` and a
+/// project's `.npmrc`, so those can still override it. Other settings in the file are kept.
+/// Returns `false` for versions that don't bundle npm.
+pub fn set(version_dir: &Path, registry: &str) -> Result<bool> {
+    let npm = npm_dir(version_dir);
+    if !npm.is_dir() {
+        return Ok(false);
+    }
+
+    let npmrc = npm.join("npmrc");
+    let contents = std::fs::read_to_string(&npmrc).unwrap_or_default();
+
+    std::fs::write(&npmrc, with_registry(&contents, registry)).map_err(|e| {
+        VoltError::WriteFileError {
+            source: e,
+            name: npmrc.display().to_string(),
+        }
+    })?;
+
+    Ok(true)
+}
+
+/// `npmrc` with its `registry=` line replaced by one for `registry`, or added at the end
+fn with_registry(npmrc: &str, registry: &str) -> String {
+    let mut lines = npmrc
+        .lines()
+        .filter(|line| {
+            let key = line.split('=').next().unwrap_or_default();
+            key.trim() != "registry"
+        })
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    lines.push(format!("registry={registry}"));
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_line_is_replaced() {
+        let npmrc = "prefix=/usr/local\nregistry = https://old.example.com/\n";
+
+        assert_eq!(
+            with_registry(npmrc, "https://npm.example.com/"),
+            "prefix=/usr/local\nregistry=https://npm.example.com/\n"
+        );
+        assert_eq!(
+            with_registry("", "https://npm.example.com/"),
+            "registry=https://npm.example.com/\n"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! The table `volt node install` prints once every version is done
+
+use comfy_table::{
+    modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Attribute, Cell, Color, Table,
+};
+use indicatif::HumanBytes;
+
+/// What happened to a requested version
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Installed,
+    AlreadyInstalled,
+    /// Saved with `--download-only`
+    Downloaded,
+    Failed,
+}
+
+impl Status {
+    fn cell(self) -> Cell {
+        match self {
+            Self::Installed => Cell::new("installed").fg(Color::Green),
+            Self::AlreadyInstalled => Cell::new("already present"),
+            Self::Downloaded => Cell::new("downloaded").fg(Color::Green),
+            Self::Failed => Cell::new("failed").fg(Color::Red),
+        }
+    }
+}
+
+/// A line of the table
+pub struct Row {
+    /// As given on the command line, e.g. `18` or a tarball's path
+    pub requested: String,
+    pub version: String,
+    pub status: Status,
+    /// Size of the download or tarball, if one was unpacked
+    pub size: Option<u64>,
+}
+
+pub fn print(rows: &[Row]) {
+    let mut table = Table::new();
+
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS);
+
+    // comfy-table styles whenever stdout is a terminal, so `--color never` has to be passed on
+    if !colored::control::SHOULD_COLORIZE.should_colorize() {
+        table.force_no_tty();
+    }
+
+    table.set_header(
+        ["Requested", "Version", "Status", "Size"]
+            .iter()
+            .map(|header| Cell::new(header).add_attribute(Attribute::Bold)),
+    );
+
+    for row in rows {
+        table.add_row(vec![
+            Cell::new(&row.requested),
+            Cell::new(&row.version),
+            row.status.cell(),
+            Cell::new(
+                row.size
+                    .map_or("-".to_string(), |size| HumanBytes(size).to_string()),
+            ),
+        ]);
+    }
+
+    println!("{table}");
+}
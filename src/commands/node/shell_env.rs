@@ -0,0 +1,109 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use clap::Parser;
+use miette::Result;
+
+use super::{current_version, get_bin_dir, resolve_installed, Shell};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Print the environment for a node version, without switching to it
+///
+/// Puts the version's binaries first on PATH in the current shell only:
+///
+///     bash/zsh: eval "$(volt node env 18)"
+///     fish:     volt node env 18 --shell fish | source
+#[derive(Debug, Parser)]
+#[clap(verbatim_doc_comment)]
+pub struct NodeEnv {
+    /// Installed version (or range, or alias), defaults to the active one
+    version: Option<String>,
+
+    /// Shell syntax to print, detected from `SHELL` by default
+    #[clap(long, arg_enum)]
+    shell: Option<Shell>,
+}
+
+#[async_trait]
+impl VoltCommand for NodeEnv {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let version = match &self.version {
+            Some(version) => {
+                let version = resolve_installed(version, &config).await?;
+                if !config.node_dir()?.join(&version).exists() {
+                    return Err(VoltError::NodeVersionNotInstalled { version }.into());
+                }
+                version
+            }
+            None => current_version(&config)?.ok_or(VoltError::NodeNoVersionSelected)?,
+        };
+
+        let bin = get_bin_dir(&config, &version)?;
+        let shell = self.shell.or_else(Shell::detect).unwrap_or(Shell::Bash);
+
+        println!("{}", prepend_path(shell, &bin));
+
+        Ok(())
+    }
+}
+
+/// A line that puts `bin` first on PATH in `shell`
+fn prepend_path(shell: Shell, bin: &Path) -> String {
+    let bin = bin.display().to_string();
+
+    match shell {
+        Shell::Fish => format!("set -x PATH \"{}\" $PATH", escape(&bin, "\\\"$")),
+        Shell::Bash | Shell::Zsh => {
+            format!("export PATH=\"{}:$PATH\"", escape(&bin, "\\\"$`"))
+        }
+    }
+}
+
+/// Backslash the `special` characters, for use inside double quotes
+fn escape(s: &str, special: &str) -> String {
+    s.chars().fold(String::new(), |mut escaped, c| {
+        if special.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_quoted_for_each_shell() {
+        let bin = Path::new("/home/u/$dir/18.19.0/bin");
+
+        assert_eq!(
+            prepend_path(Shell::Bash, bin),
+            r#"export PATH="/home/u/\$dir/18.19.0/bin:$PATH""#
+        );
+        assert_eq!(
+            prepend_path(Shell::Fish, bin),
+            r#"set -x PATH "/home/u/\$dir/18.19.0/bin" $PATH"#
+        );
+    }
+}
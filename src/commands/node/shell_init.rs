@@ -0,0 +1,85 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use std::{env, path::Path};
+
+use async_trait::async_trait;
+use clap::{ArgEnum, Parser};
+use miette::Result;
+
+use crate::cli::{VoltCommand, VoltConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, ArgEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    /// Guess the user's shell from `SHELL`
+    pub fn detect() -> Option<Self> {
+        let shell = env::var_os("SHELL")?;
+        let name = Path::new(&shell).file_name()?.to_str()?;
+
+        Self::from_str(name, true).ok()
+    }
+}
+
+/// Wraps `volt` so the shell forgets cached command paths after `volt node use`
+const POSIX_INIT: &str = r#"volt() {
+    command volt "$@"
+    local ret=$?
+    if [ "$ret" -eq 0 ] && [ "$1" = "node" ] && [ "$2" = "use" ]; then
+        __VOLT_REHASH__
+    fi
+    return $ret
+}
+"#;
+
+/// fish looks commands up on every run, so there's nothing to refresh
+const FISH_INIT: &str =
+    "# fish doesn't cache command paths, `volt node use` takes effect immediately\n";
+
+/// Print a shell function that makes `volt node use` take effect in the current shell
+///
+/// Add the output to your shell's startup file:
+///
+///     bash: eval "$(volt node shell-init bash)"   in ~/.bashrc
+///     zsh:  eval "$(volt node shell-init zsh)"    in ~/.zshrc
+///     fish: volt node shell-init fish | source    in ~/.config/fish/config.fish
+#[derive(Debug, Parser)]
+#[clap(verbatim_doc_comment)]
+pub struct NodeShellInit {
+    /// Shell to print the function for
+    #[clap(arg_enum)]
+    shell: Shell,
+}
+
+#[async_trait]
+impl VoltCommand for NodeShellInit {
+    async fn exec(self, _: VoltConfig) -> Result<()> {
+        let script = match self.shell {
+            Shell::Bash => POSIX_INIT.replace("__VOLT_REHASH__", "hash -r"),
+            Shell::Zsh => POSIX_INIT.replace("__VOLT_REHASH__", "rehash"),
+            Shell::Fish => FISH_INIT.to_string(),
+        };
+
+        print!("{script}");
+
+        Ok(())
+    }
+}
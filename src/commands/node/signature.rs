@@ -0,0 +1,112 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Verify the detached GPG signature of a release's `SHASUMS256.txt`
+//!
+//! The Node.js release keys are bundled from `keys/release-keys.asc`,
+//! see `keys/README.md` for where they come from and how to refresh them.
+//! Nothing calls this yet: installs only check signatures once the bundle holds real keys.
+
+use miette::{IntoDiagnostic, Result};
+use pgp::{Deserializable, SignedPublicKey, StandaloneSignature};
+
+use crate::core::utils::errors::VoltError;
+
+/// Name of the detached signature published next to `SHASUMS256.txt`
+pub const SIGNATURE_FILE: &str = "SHASUMS256.txt.sig";
+
+/// Armored public keys of the Node.js releasers
+const RELEASE_KEYS: &str = include_str!("keys/release-keys.asc");
+
+const KEY_BLOCK_HEADER: &str = "-----BEGIN PGP PUBLIC KEY BLOCK-----";
+
+/// Parse every key block in an armored bundle like [`RELEASE_KEYS`]
+fn parse_keys(bundle: &str) -> Result<Vec<SignedPublicKey>> {
+    bundle
+        .split(KEY_BLOCK_HEADER)
+        .skip(1)
+        .map(|block| {
+            let (key, _) = SignedPublicKey::from_string(&format!("{KEY_BLOCK_HEADER}{block}"))
+                .into_diagnostic()?;
+            Ok(key)
+        })
+        .collect()
+}
+
+/// Check that `signature` is a valid detached signature over `shasums` made by one of the
+/// bundled release keys (or one of their signing subkeys)
+pub fn verify(shasums: &[u8], signature: &[u8]) -> Result<()> {
+    verify_with(RELEASE_KEYS, shasums, signature)
+}
+
+/// [`verify`] against the keys in `bundle` instead of the bundled release keys
+fn verify_with(bundle: &str, shasums: &[u8], signature: &[u8]) -> Result<()> {
+    let keys = parse_keys(bundle)?;
+
+    if keys.is_empty() {
+        return Err(VoltError::NodeReleaseKeysMissing.into());
+    }
+
+    let signature = StandaloneSignature::from_bytes(signature).map_err(|e| {
+        VoltError::NodeSignatureInvalid {
+            reason: e.to_string(),
+        }
+    })?;
+
+    let signed_by_release_key = keys.iter().any(|key| {
+        signature.verify(key, shasums).is_ok()
+            || key
+                .public_subkeys
+                .iter()
+                .any(|subkey| signature.verify(subkey, shasums).is_ok())
+    });
+
+    if !signed_by_release_key {
+        return Err(VoltError::NodeSignatureInvalid {
+            reason: "not signed by any of the bundled Node.js release keys".to_string(),
+        }
+        .into());
+    }
+
+    tracing::debug!(
+        "verified {} against {} bundled release keys",
+        SIGNATURE_FILE,
+        keys.len()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Signed with a throwaway key made for these tests, see `keys/README.md`
+    const TEST_KEY: &str = include_str!("keys/testdata/test-key.asc");
+    const SHASUMS: &[u8] = include_bytes!("keys/testdata/SHASUMS256.txt");
+    const SIGNATURE: &[u8] = include_bytes!("keys/testdata/SHASUMS256.txt.sig");
+
+    #[test]
+    fn signature_is_checked_against_the_bundle() {
+        verify_with(TEST_KEY, SHASUMS, SIGNATURE).unwrap();
+
+        let tampered = String::from_utf8_lossy(SHASUMS).replace("linux", "darwin");
+        assert!(verify_with(TEST_KEY, tampered.as_bytes(), SIGNATURE).is_err());
+
+        // An empty bundle fails closed
+        assert!(verify_with("", SHASUMS, SIGNATURE).is_err());
+    }
+}
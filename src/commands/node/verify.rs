@@ -0,0 +1,210 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Check that installed node versions still run and are what was installed
+
+use std::{
+    env, fmt,
+    path::Path,
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+
+use super::{get_bin_dir, installed_versions, meta, resolve_installed};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Name of the node binary inside a version's bin directory
+const NODE_BINARY: &str = if cfg!(windows) { "node.exe" } else { "node" };
+
+/// How long `node --version` or `npm --version` gets to answer
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Check that an installed version isn't corrupted by running its `node --version`, and
+/// comparing its node binary against the checksum recorded when it was installed
+#[derive(Debug, Parser)]
+pub struct NodeVerify {
+    /// Version to check
+    #[clap(required_unless_present = "all", conflicts_with = "all")]
+    version: Option<String>,
+
+    /// Check every installed version
+    #[clap(long)]
+    all: bool,
+}
+
+#[async_trait]
+impl VoltCommand for NodeVerify {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let versions = match &self.version {
+            Some(version) => {
+                let version = resolve_installed(version, &config).await?;
+                if !config.node_dir()?.join(&version).exists() {
+                    return Err(VoltError::NodeVersionNotInstalled { version }.into());
+                }
+                vec![version]
+            }
+            None => {
+                let mut installed = installed_versions(&config)?;
+                if installed.is_empty() {
+                    return Err(VoltError::NodeNoVersionsInstalled.into());
+                }
+                installed.reverse();
+                installed.iter().map(|v| v.to_string()).collect()
+            }
+        };
+
+        let mut corrupt = vec![];
+
+        for version in versions {
+            match check(&config, &version) {
+                Ok(()) => println!("{:8} {}", version, "OK ✓".truecolor(0, 255, 0)),
+                Err(problem) => {
+                    println!(
+                        "{:8} {} ({})",
+                        version,
+                        "corrupt ✗".truecolor(255, 0, 0),
+                        problem
+                    );
+                    corrupt.push(version);
+                }
+            }
+        }
+
+        if !corrupt.is_empty() {
+            return Err(VoltError::NodeVersionsCorrupt {
+                versions: corrupt.join(", "),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Run the version's node binary and check it reports the version it's installed as
+///
+/// The binary is also checked against the checksum recorded when volt installed it.
+fn check(config: &VoltConfig, version: &str) -> Result<(), String> {
+    let bin = get_bin_dir(config, version).map_err(|e| e.to_string())?;
+    let node = bin.join(NODE_BINARY);
+
+    if !node.exists() {
+        return Err(format!("{} is missing", node.display()));
+    }
+
+    let version_dir = config.node_dir().map_err(|e| e.to_string())?.join(version);
+    if let Some(expected) = meta::read(&version_dir).and_then(|meta| meta.node_sha256) {
+        if meta::hash_file(&node).as_ref() != Some(&expected) {
+            return Err(format!(
+                "{} doesn't match the checksum recorded when it was installed",
+                node.display()
+            ));
+        }
+    }
+
+    node_version(&bin, version).map_err(|mismatch| mismatch.to_string())?;
+
+    Ok(())
+}
+
+/// Why a version's `node --version` doesn't vouch for it
+pub(crate) enum Mismatch {
+    /// It couldn't be run, failed, or didn't answer in time
+    Failed(String),
+    /// It answered with another version
+    Reported(String),
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed(e) => write!(f, "`node --version` failed: {e}"),
+            Self::Reported(reported) => write!(f, "`node --version` reported `{reported}`"),
+        }
+    }
+}
+
+/// Run the node binary in `bin` and check it reports `version`, returning what it printed
+pub(crate) fn node_version(bin: &Path, version: &str) -> Result<String, Mismatch> {
+    let reported = version_of(version_command(bin, NODE_BINARY)).map_err(Mismatch::Failed)?;
+
+    if reported.trim_start_matches('v') != version {
+        return Err(Mismatch::Reported(reported));
+    }
+
+    Ok(reported)
+}
+
+/// What `command --version` prints, unless it fails or doesn't answer within
+/// [`VERSION_TIMEOUT`]
+pub(crate) fn version_of(mut command: Command) -> Result<String, String> {
+    let mut child = command
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    // A hung binary is killed rather than holding up everything after it
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < VERSION_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(10))
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "didn't answer within {}s",
+                    VERSION_TIMEOUT.as_secs()
+                ));
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("exited with {}", output.status));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run one of the version's binaries with its own `bin` directory first on `PATH`, since
+/// `npm` runs on whichever `node` it finds there
+pub(crate) fn version_command(bin: &Path, name: &str) -> Command {
+    let path = env::var_os("PATH").unwrap_or_default();
+    let mut command = Command::new(bin.join(name));
+    if let Ok(path) =
+        env::join_paths(std::iter::once(bin.to_path_buf()).chain(env::split_paths(&path)))
+    {
+        command.env("PATH", path);
+    }
+
+    command
+}
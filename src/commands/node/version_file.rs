@@ -0,0 +1,70 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Find the node version a project asks for, the same way nvm and fnm do
+
+use std::path::{Path, PathBuf};
+
+use miette::Result;
+
+use crate::core::utils::errors::VoltError;
+
+/// Files that pin a node version, in order of preference
+pub const VERSION_FILES: [&str; 2] = [".nvmrc", ".node-version"];
+
+/// Walk up from `start` and return the closest version file
+///
+/// `.nvmrc` wins over `.node-version` when both live in the same directory.
+pub fn find(start: &Path) -> Option<PathBuf> {
+    start.ancestors().find_map(|dir| {
+        VERSION_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|file| file.is_file())
+    })
+}
+
+/// Read the version pinned by the closest version file above `start`
+pub fn read(start: &Path) -> Result<String> {
+    let file = find(start).ok_or_else(|| VoltError::NodeVersionFileNotFound {
+        dir: start.display().to_string(),
+    })?;
+
+    let contents = std::fs::read_to_string(&file).map_err(|e| VoltError::ReadFileError {
+        source: e,
+        name: file.display().to_string(),
+    })?;
+
+    tracing::debug!("using node version from {}", file.display());
+
+    Ok(parse(&contents))
+}
+
+/// Write `version` to the version file at `path`, replacing what was pinned before
+pub fn write(path: &Path, version: &str) -> Result<()> {
+    std::fs::write(path, format!("{version}\n")).map_err(|e| {
+        VoltError::WriteFileError {
+            source: e,
+            name: path.display().to_string(),
+        }
+        .into()
+    })
+}
+
+/// Extract the version from the contents of a version file, dropping whitespace and a leading `v`
+pub fn parse(contents: &str) -> String {
+    super::strip_v(contents.trim()).to_string()
+}
@@ -0,0 +1,74 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use async_trait::async_trait;
+use clap::Parser;
+use miette::Result;
+
+use super::{current_version, get_bin_dir, resolve_installed};
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    core::utils::errors::VoltError,
+};
+
+/// Suffixes a binary may have, windows ships `.exe`s and `.cmd` shims
+const SUFFIXES: &[&str] = if cfg!(windows) {
+    &["", ".exe", ".cmd"]
+} else {
+    &[""]
+};
+
+/// Print the path of a binary, e.g. `npm`, from the active node version
+#[derive(Debug, Parser)]
+pub struct NodeWhich {
+    /// Name of the binary
+    binary: String,
+
+    /// Look the binary up in this installed version instead of the active one
+    #[clap(long)]
+    version: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for NodeWhich {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let version = match &self.version {
+            Some(version) => {
+                let version = resolve_installed(version, &config).await?;
+                if !config.node_dir()?.join(&version).exists() {
+                    return Err(VoltError::NodeVersionNotInstalled { version }.into());
+                }
+                version
+            }
+            None => current_version(&config)?.ok_or(VoltError::NodeNoVersionSelected)?,
+        };
+
+        let bin_dir = get_bin_dir(&config, &version)?;
+
+        let path = SUFFIXES
+            .iter()
+            .map(|suffix| bin_dir.join(format!("{}{}", self.binary, suffix)))
+            .find(|path| path.exists())
+            .ok_or(VoltError::NodeBinaryNotFound {
+                binary: self.binary,
+                version,
+            })?;
+
+        println!("{}", path.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Editing the user's `PATH` in the registry on windows
+
+use std::{ffi::OsStr, io, os::windows::ffi::OsStrExt, path::Path, ptr};
+
+use winapi::{
+    shared::minwindef::LPARAM,
+    um::winuser::{SendMessageTimeoutW, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE},
+};
+use winreg::{
+    enums::{RegType, HKEY_CURRENT_USER, KEY_READ, KEY_WRITE},
+    RegKey, RegValue,
+};
+
+/// Registry key holding the user's environment variables
+const ENVIRONMENT: &str = "Environment";
+
+/// Whether two `PATH` entries name the same directory
+fn same_entry(a: &str, b: &str) -> bool {
+    a.trim_end_matches('\\')
+        .eq_ignore_ascii_case(b.trim_end_matches('\\'))
+}
+
+/// Whether `dir` is one of the entries of the `;`-separated `path`
+pub fn contains(path: &str, dir: &Path) -> bool {
+    let dir = dir.to_string_lossy();
+
+    path.split(';').any(|entry| same_entry(entry, &dir))
+}
+
+/// `path` with `dir` appended if it isn't there yet, and without empty or repeated entries
+///
+/// An entry already on `path` keeps its place, so its precedence doesn't change.
+fn with_entry(path: &str, dir: &str) -> String {
+    let mut entries: Vec<&str> = vec![];
+    for entry in path.split(';').chain(Some(dir)) {
+        if !entry.is_empty() && !entries.iter().any(|e| same_entry(e, entry)) {
+            entries.push(entry);
+        }
+    }
+
+    entries.join(";")
+}
+
+/// UTF-16, nul terminated, as the registry and win32 APIs expect
+fn to_wide(s: &str) -> Vec<u16> {
+    OsStr::new(s).encode_wide().chain(Some(0)).collect()
+}
+
+/// Add `dir` to the end of the user's `PATH` unless it's already on it, dropping duplicates
+///
+/// Returns whether `PATH` changed. Other programs are told about the change, so new
+/// terminals pick it up without logging out.
+pub fn add_to_user_path(dir: &Path) -> io::Result<bool> {
+    let key = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey_with_flags(ENVIRONMENT, KEY_READ | KEY_WRITE)?;
+    let dir = dir.to_string_lossy();
+
+    let path: String = match key.get_value("Path") {
+        Ok(path) => path,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+
+    let updated = with_entry(&path, &dir);
+
+    if updated == path {
+        return Ok(false);
+    }
+
+    // Written as REG_EXPAND_SZ so entries like `%USERPROFILE%\bin` keep expanding
+    let bytes = to_wide(&updated)
+        .into_iter()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect();
+    key.set_raw_value(
+        "Path",
+        &RegValue {
+            bytes,
+            vtype: RegType::REG_EXPAND_SZ,
+        },
+    )?;
+
+    let environment = to_wide(ENVIRONMENT);
+    unsafe {
+        SendMessageTimeoutW(
+            HWND_BROADCAST,
+            WM_SETTINGCHANGE,
+            0,
+            environment.as_ptr() as LPARAM,
+            SMTO_ABORTIFHUNG,
+            5000,
+            ptr::null_mut(),
+        );
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_matched_exactly() {
+        let bin = r"C:\Users\me\.volt\bin";
+
+        assert_eq!(
+            with_entry(r"C:\Windows;", bin),
+            format!(r"C:\Windows;{bin}")
+        );
+        assert_eq!(
+            with_entry(r"C:\Users\me\.volt\bin2", bin),
+            format!(r"C:\Users\me\.volt\bin2;{bin}")
+        );
+        assert_eq!(
+            with_entry(r"c:\users\me\.volt\bin\;C:\Windows;C:\Windows", bin),
+            r"c:\users\me\.volt\bin\;C:\Windows"
+        );
+        assert!(!contains(r"C:\Users\me\.volt\bin2", Path::new(bin)));
+    }
+}
@@ -17,7 +17,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
 
-use std::fmt;
+use std::{fmt, path::Path};
 
 #[derive(Serialize, Deserialize)]
 pub enum PackageManager {
@@ -70,6 +70,34 @@ impl PackageManager {
     }
 }
 
+impl PackageManager {
+    /// Detect which package manager a project expects from the lockfile in `dir`
+    ///
+    /// Projects with a `package-lock.json` or only a `package.json` are installed with volt.
+    /// Returns `None` if `dir` isn't a package at all.
+    pub fn detect(dir: &Path) -> Option<Self> {
+        if dir.join("pnpm-lock.yaml").exists() {
+            Some(Self::Pnpm)
+        } else if dir.join("yarn.lock").exists() {
+            Some(Self::Yarn)
+        } else if dir.join("package-lock.json").exists() || dir.join("package.json").exists() {
+            Some(Self::Volt)
+        } else {
+            None
+        }
+    }
+
+    /// The program and arguments that install a project's dependencies
+    pub const fn install_command(&self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Self::Volt => ("volt", &["install"]),
+            Self::Yarn => ("yarn", &[]),
+            Self::Pnpm => ("pnpm", &["install"]),
+            Self::Npm => ("npm", &["install"]),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ChangePackageManger {
     pub template: PackageManager,
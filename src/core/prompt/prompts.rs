@@ -213,3 +213,56 @@ impl<'i> Select<'i> {
         input.interact()
     }
 }
+
+/// Prompt that allows the user to check any number of items from a list
+#[derive(Debug)]
+pub struct MultiSelect<'i> {
+    /// Message for the prompt
+    pub message: Cow<'i, str>,
+
+    /// How many items to show at once, the rest are scrolled to
+    pub max_length: Option<usize>,
+
+    /// Items that can be selected
+    pub items: Vec<Cow<'i, str>>,
+}
+
+impl<'i> MultiSelect<'i> {
+    /// The indices of the checked items, `None` if the prompt was cancelled with `Esc` or `q`
+    pub fn run(&self) -> Result<Option<Vec<usize>>> {
+        if self.items.is_empty() {
+            return Ok(Some(vec![]));
+        }
+
+        let theme = ColorfulTheme {
+            defaults_style: console::Style::new(),
+            prompt_style: console::Style::new().bold(),
+            prompt_prefix: console::style(String::from("?")).yellow().bright(),
+            prompt_suffix: console::style(String::from(">")).blue().dim(),
+            success_prefix: console::style(String::from("✔")).green().bright(),
+            success_suffix: console::style(String::from("·")).blue().dim(),
+            error_prefix: console::style(String::from("❌")).bright().red(),
+            error_style: console::Style::new(),
+            hint_style: console::Style::new().bold(),
+            values_style: console::Style::new(),
+            active_item_style: console::Style::new().bold(),
+            inactive_item_style: console::Style::new(),
+            active_item_prefix: console::style(String::from(">")).bright().cyan(),
+            inactive_item_prefix: console::style(String::from(" ")),
+            checked_item_prefix: console::style(String::from("✔")).green().bright(),
+            unchecked_item_prefix: console::style(String::from("·")).dim(),
+            picked_item_prefix: console::style(String::from("")),
+            unpicked_item_prefix: console::style(String::from("")),
+            inline_selections: false,
+        };
+
+        let mut input = dialoguer::MultiSelect::with_theme(&theme);
+
+        input.with_prompt(self.message.clone()).items(&self.items);
+        if let Some(max_length) = self.max_length {
+            input.max_length(max_length);
+        }
+
+        input.interact_opt()
+    }
+}
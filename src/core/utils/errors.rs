@@ -34,6 +34,13 @@ pub enum VoltError {
     #[diagnostic(code(volt::environment::home_dir))]
     GetHomeDirError,
 
+    #[error("could not determine a data directory")]
+    #[diagnostic(
+        code(volt::environment::data_dir),
+        help("set VOLT_HOME to the directory volt should keep node versions in")
+    )]
+    GetDataDirError,
+
     #[error("failed to get the name of the current directory")]
     #[diagnostic(code(volt::environment::current_dir_name))]
     GetCurrentDirNameError,
@@ -132,7 +139,263 @@ pub enum VoltError {
     #[diagnostic(code(volt::git::parse))]
     GitConfigParseError { error_text: String },
 
+    #[error("no LTS release of node matches `{codename}`")]
+    #[diagnostic(
+        code(volt::node::lts_not_found),
+        help("LTS codenames look like `hydrogen` or `gallium`")
+    )]
+    NodeLtsNotFound { codename: String },
+
+    #[error("`{file}` is not listed in the release's SHASUMS256.txt")]
+    #[diagnostic(
+        code(volt::node::checksum_missing),
+        help("pass `--skip-checksum` to install without verifying the download")
+    )]
+    NodeChecksumMissing { file: String },
+
+    #[error("checksum mismatch for `{file}`: expected {expected}, got {actual}")]
+    #[diagnostic(
+        code(volt::node::checksum_mismatch),
+        help("the download may be corrupted or tampered with, try again or use another mirror")
+    )]
+    NodeChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("this build of volt has no Node.js release keys bundled")]
+    #[diagnostic(
+        code(volt::node::release_keys_missing),
+        help("see src/commands/node/keys/README.md for how to refresh the bundled keys")
+    )]
+    NodeReleaseKeysMissing,
+
+    #[error("failed to verify the signature of SHASUMS256.txt: {reason}")]
+    #[diagnostic(code(volt::node::signature_invalid))]
+    NodeSignatureInvalid { reason: String },
+
+    #[error("No version selected")]
+    #[diagnostic(
+        code(volt::node::no_version_selected),
+        help("run `volt node use <version>` to select one")
+    )]
+    NodeNoVersionSelected,
+
+    #[error("no version given and no `.nvmrc` or `.node-version` found in `{dir}` or its parents")]
+    #[diagnostic(
+        code(volt::node::version_file_not_found),
+        help("pass a version, e.g. `volt node use 18`, or add an `.nvmrc` to your project")
+    )]
+    NodeVersionFileNotFound { dir: String },
+
+    #[error("`{url}` is not a valid mirror URL: {reason}")]
+    #[diagnostic(
+        code(volt::node::invalid_mirror),
+        help("check `VOLT_NODE_MIRROR` or `--node-mirror`, e.g. `https://npmmirror.com/mirrors/node`")
+    )]
+    InvalidMirrorUrl { url: String, reason: String },
+
+    #[error("`{url}` is not a valid proxy URL: {reason}")]
+    #[diagnostic(
+        code(volt::http::invalid_proxy),
+        help("proxies look like `http://proxy.example.com:8080`")
+    )]
+    InvalidProxyUrl { url: String, reason: String },
+
+    #[error("`{url}` is not a valid npm registry URL: {reason}")]
+    #[diagnostic(
+        code(volt::node::invalid_registry),
+        help("check `--registry`, `--npm-registry` or `VOLT_NPM_REGISTRY`, e.g. `https://registry.npmmirror.com`")
+    )]
+    InvalidRegistryUrl { url: String, reason: String },
+
+    #[error("failed to clone `{repository}`")]
+    #[diagnostic(code(volt::git::clone))]
+    GitCloneError { repository: String },
+
+    #[error("`{command}` failed in {dir}")]
+    #[diagnostic(
+        code(volt::clone::install),
+        help("the repository was cloned, run the install again in it once the problem is fixed")
+    )]
+    CloneInstallError { command: String, dir: String },
+
+    #[error("node {version} is not installed")]
+    #[diagnostic(
+        code(volt::node::not_installed),
+        help("run `volt node install {version}` to install it")
+    )]
+    NodeVersionNotInstalled { version: String },
+
+    #[error("node {version} is the version in use")]
+    #[diagnostic(
+        code(volt::node::version_in_use),
+        help("switch to another version with `volt node use` first, or pass `--force` to remove it anyway")
+    )]
+    NodeVersionInUse { version: String },
+
+    #[error("couldn't remove node {versions}")]
+    #[diagnostic(code(volt::node::not_removed))]
+    NodeVersionsNotRemoved { versions: String },
+
+    #[error("no node versions are installed in `{dir}`")]
+    #[diagnostic(
+        code(volt::node::nothing_to_migrate),
+        help("pass the old data directory, the one with `node` in it")
+    )]
+    NodeNothingToMigrate { dir: String },
+
+    #[error("`{dir}` is the current data directory")]
+    #[diagnostic(
+        code(volt::node::migrate_to_itself),
+        help("pass `--data-dir` or set `VOLT_HOME` to the new location first")
+    )]
+    NodeMigrateToItself { dir: String },
+
+    #[error("couldn't move node {versions}")]
+    #[diagnostic(
+        code(volt::node::not_migrated),
+        help("move them into the new node directory by hand, or reinstall them")
+    )]
+    NodeVersionsNotMigrated { versions: String },
+
+    #[error("node {versions} failed verification")]
+    #[diagnostic(
+        code(volt::node::corrupt),
+        help("run `volt node reinstall <version>` to replace a corrupted version")
+    )]
+    NodeVersionsCorrupt { versions: String },
+
+    #[error("node {version} has no {build} build")]
+    #[diagnostic(
+        code(volt::node::no_build),
+        help("run `volt node list --remote` to find another version")
+    )]
+    NodeNoBuild { version: String, build: String },
+
+    #[error("there's no cached node release index to use offline")]
+    #[diagnostic(
+        code(volt::node::offline_no_index),
+        help("run the command once without `--offline` to cache it")
+    )]
+    NodeOfflineNoIndex,
+
+    #[error("node {versions} would have to be downloaded, which `--offline` forbids")]
+    #[diagnostic(
+        code(volt::node::not_available_offline),
+        help("install from a release tarball on disk, or run without `--offline`")
+    )]
+    NodeNotAvailableOffline { versions: String },
+
+    #[error("`corepack enable` failed ({status})")]
+    #[diagnostic(code(volt::node::corepack))]
+    NodeCorepackFailed { status: String },
+
+    #[error("{failed} check(s) failed")]
+    #[diagnostic(code(volt::node::doctor))]
+    NodeDoctorFailed { failed: usize },
+
+    #[error("`{file}` isn't a node release for this platform")]
+    #[diagnostic(
+        code(volt::node::invalid_artifact),
+        help("expected a file named like `{expected}`")
+    )]
+    NodeInvalidArtifact { file: String, expected: String },
+
+    #[error("no installed version of node matches `{requested}`")]
+    #[diagnostic(
+        code(volt::node::no_installed_match),
+        help("installed versions: {available}")
+    )]
+    NodeNoInstalledMatch {
+        requested: String,
+        available: String,
+    },
+
+    #[error("`{name}` can't be used as an alias name")]
+    #[diagnostic(
+        code(volt::node::invalid_alias_name),
+        help("alias names can't look like a version or range, or be `latest` or `lts`")
+    )]
+    NodeInvalidAliasName { name: String },
+
+    #[error("no alias named `{name}`")]
+    #[diagnostic(
+        code(volt::node::alias_not_found),
+        help("run `volt node alias list` to see every alias")
+    )]
+    NodeAliasNotFound { name: String },
+
+    #[error("node {version} has no `{binary}` binary")]
+    #[diagnostic(code(volt::node::binary_not_found))]
+    NodeBinaryNotFound { binary: String, version: String },
+
+    #[error("no version satisfies `{range}`")]
+    #[diagnostic(
+        code(volt::node::no_version_satisfies),
+        help("run `volt node list --remote` to see every available version")
+    )]
+    NodeNoVersionSatisfies { range: String },
+
+    #[error("`{version}` is not a valid version or range")]
+    #[diagnostic(
+        code(volt::node::invalid_version),
+        help("versions look like `18.19.0`, ranges like `^18` or `>=16 <18`")
+    )]
+    NodeInvalidVersion { version: String },
+
+    #[error("can't extract {artifact}, unpacking .tar.xz releases isn't supported on windows")]
+    #[diagnostic(
+        code(volt::node::unsupported_archive),
+        help("pass `--compression gz` to download the .tar.gz instead")
+    )]
+    NodeUnsupportedArchive { artifact: String },
+
+    #[error("installation cancelled")]
+    #[diagnostic(code(volt::node::install_cancelled))]
+    NodeInstallCancelled,
+
+    #[error("`--interactive` needs a terminal to pick versions in")]
+    #[diagnostic(
+        code(volt::node::not_interactive),
+        help("pass the versions to install instead, e.g. `volt node install 18`")
+    )]
+    NodeNotInteractive,
+
+    #[error("failed to install node {failed}")]
+    #[diagnostic(code(volt::node::install_failed), help("{help}"))]
+    NodeInstallFailed { failed: String, help: String },
+
+    #[error("node {version} does not exist")]
+    #[diagnostic(code(volt::node::unknown_version), help("{help}"))]
+    NodeUnknownVersion { version: String, help: String },
+
+    #[error("no versions of node are installed")]
+    #[diagnostic(
+        code(volt::node::none_installed),
+        help("run `volt node install <version>` to install one")
+    )]
+    NodeNoVersionsInstalled,
+
+    #[error("the command exited with code {code}")]
+    #[diagnostic(code(volt::node::exec_failed))]
+    NodeExecFailed { code: i32 },
+
     #[error("an unknown error occured.")]
     #[diagnostic(code(volt::unknown))]
     UnknownError,
 }
+
+impl VoltError {
+    /// Code volt exits with when it fails with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            // What shells report for a process stopped by Ctrl-C
+            Self::NodeInstallCancelled => 130,
+            // `volt node exec` exits with whatever the command it ran did
+            Self::NodeExecFailed { code } => *code,
+            _ => 1,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+/*
+ *
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! volt as a library
+//!
+//! The [`node`] module manages node versions the same way `volt node` does, for tools that
+//! would rather call volt than shell out to it. Everything is configured through a
+//! [`cli::VoltConfig`], e.g. `VoltConfig::parse_from(["volt", "--data-dir", "/opt/volt"])`.
+
+#![allow(unused)]
+
+pub mod cli;
+pub mod commands;
+pub mod core;
+pub mod node;
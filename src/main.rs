@@ -21,22 +21,55 @@ use mimalloc::MiMalloc;
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
 
-mod cli;
-mod commands;
-mod core;
-
 use std::{io::stdin, str::FromStr, time::Instant};
 
 use tracing::Level;
 use tracing_subscriber::EnvFilter;
 
-use crate::cli::{VoltCli, VoltCommand};
+use volt::{
+    cli::{VoltCli, VoltCommand},
+    core::utils::errors::VoltError,
+};
+
+/// Apply `--color` to everything that colors output: our own text, progress bars and prompts,
+/// and error reports
+fn init_color(color: Option<bool>) {
+    let color = match color {
+        Some(color) => color,
+        None => return,
+    };
+
+    colored::control::set_override(color);
+    dialoguer::console::set_colors_enabled(color);
+    dialoguer::console::set_colors_enabled_stderr(color);
+
+    // Reports are only styled on a terminal, elsewhere they're already plain
+    if !color && !dialoguer::console::Term::stderr().is_term() {
+        return;
+    }
+
+    // Nothing else has installed a hook yet
+    let _ = miette::set_hook(Box::new(move |_| {
+        let theme = if color {
+            miette::GraphicalTheme::unicode()
+        } else {
+            miette::GraphicalTheme::unicode_nocolor()
+        };
+
+        Box::new(miette::GraphicalReportHandler::new_themed(theme))
+    }));
+}
 
 //#[tokio::main(worker_threads = 6)]
 //#[tokio::main(flavor = "current_thread")]
 fn main() -> miette::Result<()> {
     let body = async {
+        let app = VoltCli::new();
+        let color = app.config.color();
+        init_color(color);
+
         tracing_subscriber::fmt()
+            .with_ansi(color.unwrap_or(true))
             .with_max_level(Level::TRACE)
             .with_env_filter(
                 EnvFilter::try_from_default_env()
@@ -46,28 +79,43 @@ fn main() -> miette::Result<()> {
             .init();
 
         if cfg!(windows) {
-            core::utils::enable_ansi_support().unwrap();
+            volt::core::utils::enable_ansi_support().unwrap();
         }
 
         let start = Instant::now();
-
-        let app = VoltCli::new();
+        let silent = app.cmd.is_silent();
 
         app.cmd.exec(app.config).await?;
 
-        println!("Finished in {:.2}s", start.elapsed().as_secs_f32());
+        // On stderr so generated scripts and other output can be piped
+        if !silent {
+            eprintln!("Finished in {:.2}s", start.elapsed().as_secs_f32());
+        }
 
         Ok(())
     };
 
-    tokio::runtime::Builder::new_multi_thread()
+    let result: miette::Result<()> = tokio::runtime::Builder::new_multi_thread()
         .worker_threads(6)
         .max_blocking_threads(6)
         .thread_name("volt")
         .enable_all()
         .build()
         .expect("Failed to build the runtime")
-        .block_on(body)
+        .block_on(body);
+
+    // Everything has been dropped by now, so exiting with another code skips no cleanup
+    if let Err(report) = &result {
+        let code = report
+            .downcast_ref::<VoltError>()
+            .map_or(1, VoltError::exit_code);
+        if code != 1 {
+            eprintln!("Error: {report:?}");
+            std::process::exit(code);
+        }
+    }
+
+    result
 
     /*
      *tokio::runtime::Builder::new_multi_thread()
@@ -0,0 +1,135 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Managing node versions from Rust
+//!
+//! These are the operations `volt node` is built on, returning what they did instead of
+//! printing it; the commands only add the output. The data directory, mirrors and network
+//! limits all come from the [`VoltConfig`].
+
+use std::{path::PathBuf, sync::Arc};
+
+use indicatif::ProgressBar;
+use miette::{IntoDiagnostic, Result, WrapErr};
+use node_semver::Version;
+
+use crate::{
+    cli::VoltConfig,
+    commands::node::{self as cmd, lock, mirror, Target},
+    core::utils::errors::VoltError,
+};
+
+mod release;
+mod resolve;
+mod select;
+
+pub(crate) use release::{check_builds, Release};
+pub(crate) use resolve::{
+    latest_lts, needs_64_bit, partial_range, resolve_in, strip_v, unalias, Releases,
+};
+#[cfg(unix)]
+pub(crate) use select::remove_link;
+pub(crate) use select::select;
+#[cfg(windows)]
+pub(crate) use select::windows_shims;
+
+/// A version of node in the data directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstalledVersion {
+    pub version: Version,
+    /// The version directory
+    pub path: PathBuf,
+    /// Whether it's the selected version
+    pub current: bool,
+}
+
+/// Every installed version, oldest first
+pub fn installed(config: &VoltConfig) -> Result<Vec<InstalledVersion>> {
+    let node_dir = config.node_dir()?;
+    let current = current(config)?;
+
+    let versions = cmd::installed_versions(config)?;
+
+    Ok(versions
+        .into_iter()
+        .map(|version| InstalledVersion {
+            path: node_dir.join(version.to_string()),
+            current: current.as_ref() == Some(&version),
+            version,
+        })
+        .collect())
+}
+
+/// The selected version, if there is one and it's still installed
+pub fn current(config: &VoltConfig) -> Result<Option<Version>> {
+    Ok(cmd::active_version(config)?.and_then(|version| version.parse().ok()))
+}
+
+/// The release a version, range, alias, `latest` or `lts[/<codename>]` stands for
+pub async fn resolve(config: &VoltConfig, requested: &str) -> Result<Version> {
+    let index = cmd::fetch_node_versions(config, &config.http_client()?).await?;
+    resolve_in(config, &Releases::new(&index), requested)
+}
+
+/// Download and install a release, replacing an existing install of the same version
+///
+/// The download is verified against the release's `SHASUMS256.txt`. Returns the version
+/// directory.
+///
+/// The data directory is locked meanwhile, so this waits for a running `volt node` command
+/// that changes it.
+pub async fn install(config: &VoltConfig, version: &Version) -> Result<PathBuf> {
+    let _lock = lock::acquire(config).await?;
+
+    let client = config.http_client()?;
+    let index = cmd::fetch_node_versions(config, &client).await?;
+    check_builds(
+        &Releases::new(&index),
+        std::slice::from_ref(version),
+        Target::NATIVE,
+    )?;
+
+    std::fs::create_dir_all(config.node_dir()?).map_err(VoltError::CreateDirError)?;
+
+    let mirrors = Arc::new(mirror::ordered(&client, config).await?);
+    let release = Release::new(config, client, mirrors, Target::NATIVE, version.clone())?;
+    let dest = release.dest.clone();
+
+    tokio::task::spawn_blocking(move || release.install(&ProgressBar::hidden()))
+        .await
+        .into_diagnostic()??;
+
+    Ok(dest)
+}
+
+/// Select an installed version, linking its binaries into the link directory
+///
+/// Returns the link directory, which has to be on `PATH` for the version to be used. On
+/// windows it's added to the user's PATH if it isn't there, which only new terminals see.
+///
+/// Locks the data directory like [`install`].
+pub async fn set_current(config: &VoltConfig, version: &Version) -> Result<PathBuf> {
+    let _lock = lock::acquire(config).await?;
+
+    let switched = select(config, &version.to_string(), false)?;
+    if let Some((link, e)) = switched.failed.into_iter().next() {
+        return Err(e)
+            .into_diagnostic()
+            .wrap_err(format!("failed to link {}", link.display()));
+    }
+
+    config.bin_dir()
+}
@@ -0,0 +1,250 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Download, verify and unpack releases
+
+use std::{path::PathBuf, sync::Arc};
+
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use node_semver::Version;
+
+use super::Releases;
+use crate::{
+    cli::VoltConfig,
+    commands::node::{checksum, download, extract, meta, mirror, Compression, Target},
+    core::utils::errors::VoltError,
+};
+
+/// Fail unless every one of `versions` has a build for `target`
+///
+/// Old releases and less common platforms don't have a build of every version, and the
+/// mirror would only answer with a 404 for the missing artifact.
+pub(crate) fn check_builds(
+    releases: &Releases,
+    versions: &[Version],
+    target: Target,
+) -> Result<()> {
+    let key = target.key();
+
+    for version in versions {
+        let has_build = releases
+            .get(version)
+            .map_or(false, |n| n.files.contains(&key));
+
+        if !has_build {
+            return Err(VoltError::NodeNoBuild {
+                version: version.to_string(),
+                build: target.to_string(),
+            }
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// A release to download, verify and unpack
+pub(crate) struct Release {
+    pub version: Version,
+    pub target: Target,
+    /// Version directory to unpack into, replaced if it exists
+    pub dest: PathBuf,
+    /// Mirrors to try in order
+    pub mirrors: Arc<Vec<String>>,
+    /// Directory downloads are kept in until they're unpacked
+    pub downloads: PathBuf,
+    pub client: reqwest::Client,
+    pub limits: download::Limits,
+    pub skip_checksum: bool,
+    pub compression: Compression,
+    /// Only use an artifact that's already in `downloads`, with `--offline`
+    pub offline: bool,
+}
+
+impl Release {
+    /// The release of `version` for `target`, fetched from `mirrors`, verified against its
+    /// checksums and installed into the target's directory in the node directory
+    pub(crate) fn new(
+        config: &VoltConfig,
+        client: reqwest::Client,
+        mirrors: Arc<Vec<String>>,
+        target: Target,
+        version: Version,
+    ) -> Result<Self> {
+        Ok(Self {
+            dest: target
+                .install_dir(&config.node_dir()?)
+                .join(version.to_string()),
+            version,
+            target,
+            mirrors,
+            downloads: config.volt_dir()?.join(download::DOWNLOADS_DIR),
+            client,
+            limits: download::Limits::new(config),
+            skip_checksum: false,
+            compression: Compression::DEFAULT,
+            offline: config.offline(),
+        })
+    }
+
+    /// Download the release and unpack it into `dest`, following along on `pb`
+    ///
+    /// A `.tar.xz` that fails to unpack is replaced by the release's `.tar.gz`.
+    ///
+    /// Returns the size of the artifact that was unpacked.
+    ///
+    /// This blocks on the downloads, so it has to run on a blocking thread inside the runtime.
+    pub(crate) fn install(&self, pb: &ProgressBar) -> Result<u64> {
+        let artifact = self.target.artifact(&self.version, self.compression);
+        let (url, sha256) = self.fetch(&artifact, pb)?;
+
+        let (url, sha256, artifact) = match self.unpack(&artifact, pb) {
+            Ok(()) => (url, sha256, artifact),
+            Err(e) if artifact.ends_with(".tar.xz") => {
+                let fallback = self.target.artifact(&self.version, Compression::Gz);
+                pb.suspend(|| eprintln!("{} {e}, trying {fallback} instead", "warning:".yellow()));
+
+                let (url, sha256) = self.fetch(&fallback, pb)?;
+                self.unpack(&fallback, pb)?;
+                (url, sha256, fallback)
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Only kept until it's unpacked
+        let path = self.downloads.join(&artifact);
+        let size = std::fs::metadata(&path).map_or(0, |m| m.len());
+        let _ = std::fs::remove_file(&path);
+
+        let node = self.target.node_binary(&self.dest);
+        let meta = meta::InstallMeta::new(&self.version.to_string(), url, sha256, &node);
+        meta::write(&self.dest, &meta);
+
+        Ok(size)
+    }
+
+    /// Download the release's artifact and verify it without unpacking it, returning where
+    /// it was saved in `downloads`
+    ///
+    /// This blocks on the downloads like [`Release::install`].
+    pub(crate) fn download(&self, pb: &ProgressBar) -> Result<PathBuf> {
+        let artifact = self.target.artifact(&self.version, self.compression);
+        self.fetch(&artifact, pb)?;
+
+        Ok(self.downloads.join(artifact))
+    }
+
+    /// Download one of the release's artifacts into `downloads` and verify it, returning its
+    /// URL and sha256
+    ///
+    /// The artifact is downloaded to a `.part` file in `downloads`, which is resumed if an
+    /// earlier download was interrupted, and only renamed to the artifact's name once it's
+    /// complete and verified. Offline, only a complete artifact can be used, and it isn't
+    /// verified again because the checksums can't be fetched.
+    fn fetch(&self, artifact: &str, pb: &ProgressBar) -> Result<(String, String)> {
+        let i = &self.version;
+        let (client, limits) = (&self.client, self.limits);
+        let done = self.downloads.join(artifact);
+        let part = self.downloads.join(format!("{artifact}.part"));
+        std::fs::create_dir_all(&self.downloads).map_err(VoltError::CreateDirError)?;
+
+        let handle = tokio::runtime::Handle::current();
+
+        // An earlier run may have downloaded it but not unpacked it, it's verified again below
+        let (downloaded, mirror) = if done.exists() {
+            tracing::debug!("Reusing {:?}", done);
+            (&done, self.mirrors[0].as_str())
+        } else if self.offline {
+            return Err(VoltError::NodeNotAvailableOffline {
+                versions: i.to_string(),
+            }
+            .into());
+        } else {
+            let part = &part;
+            let ((), mirror) = handle.block_on(mirror::with_fallback(&self.mirrors, |mirror| {
+                let url = format!("{mirror}/v{i}/{artifact}");
+                tracing::debug!("Downloading {}", url);
+                async move { download::download(client, &url, part, limits, pb).await }
+            }))?;
+            (part, mirror)
+        };
+
+        // Hashed as it's read, the download can be far bigger than we'd want in memory
+        let sha256 = checksum::sha256_file(downloaded).map_err(|e| VoltError::ReadFileError {
+            source: e,
+            name: downloaded.display().to_string(),
+        })?;
+        tracing::debug!("Received {} from {}", artifact, mirror);
+
+        // The checksums have to come from the mirror the artifact came from
+        let shasums_url = format!("{mirror}/v{i}/{}", checksum::SHASUMS_FILE);
+
+        // Back to a spinner while verifying and extracting
+        pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}"));
+
+        // Verify the tarball before it's unpacked
+        if !self.skip_checksum && !self.offline {
+            let shasums = handle.block_on(download::text(client, &shasums_url, limits))?;
+
+            if let Err(e) = checksum::verify(&shasums, artifact, &sha256) {
+                tracing::debug!("Checksum of {} doesn't match: {}", artifact, e);
+                // Resuming a corrupt download would only corrupt it further
+                let _ = std::fs::remove_file(downloaded);
+                pb.abandon_with_message(format!(
+                    "{:8} {:10}",
+                    i.to_string().truecolor(255, 0, 0),
+                    "Checksum mismatch ✗"
+                ));
+                return Err(e);
+            }
+            tracing::debug!("Checksum of {} matches", artifact);
+        }
+
+        if downloaded == &part {
+            std::fs::rename(&part, &done)
+                .into_diagnostic()
+                .wrap_err(format!("failed to move {} into place", part.display()))?;
+        }
+
+        Ok((format!("{mirror}/v{i}/{artifact}"), sha256))
+    }
+
+    /// Unpack the fetched `artifact` from `downloads` into `dest`
+    fn unpack(&self, artifact: &str, pb: &ProgressBar) -> Result<()> {
+        let i = &self.version;
+
+        pb.set_message(format!(
+            "{:8} {:10}",
+            i.to_string().truecolor(125, 125, 125),
+            "Extracting"
+        ));
+
+        let path = self.downloads.join(artifact);
+        let archive = std::fs::File::open(&path).map_err(|e| VoltError::ReadFileError {
+            source: e,
+            name: path.display().to_string(),
+        })?;
+        extract::unpack(archive, artifact, &self.dest, pb)?;
+
+        // The spinner style hides the finished extraction bar
+        pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}"));
+        tracing::debug!("Extracted {} to {:?}", artifact, self.dest);
+
+        Ok(())
+    }
+}
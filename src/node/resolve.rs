@@ -0,0 +1,255 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Turn what the user asked for into a release on the mirror
+
+use miette::Result;
+use node_semver::{Range, Version};
+
+use crate::{
+    cli::VoltConfig,
+    commands::node::{alias, NodeVersion},
+    core::utils::errors::VoltError,
+};
+
+/// Resolve a version, range, alias, `latest` or `lts[/<codename>]` to one of `releases`
+pub(crate) fn resolve_in(
+    config: &VoltConfig,
+    releases: &Releases,
+    requested: &str,
+) -> Result<Version> {
+    let requested = unalias(config, requested)?;
+    let requested = strip_v(&requested);
+
+    match requested {
+        "latest" => releases
+            .sorted
+            .first()
+            .map(|n| n.version.clone())
+            .ok_or_else(|| {
+                VoltError::NodeNoVersionSatisfies {
+                    range: requested.to_string(),
+                }
+                .into()
+            }),
+        "lts" | "lts/*" => latest_lts(releases.index, None),
+        _ => match requested.strip_prefix("lts/") {
+            Some(codename) => latest_lts(releases.index, Some(codename)),
+            None => releases.resolve(requested),
+        },
+    }
+}
+
+/// Whether `requested` only matches releases from 10.0.0 on, which have no 32 bit unix builds
+pub(crate) fn needs_64_bit(requested: &str) -> bool {
+    if let Ok(version) = requested.parse::<Version>() {
+        version >= Version::parse("10.0.0").unwrap()
+    } else if let Ok(range) = requested.parse::<Range>() {
+        Range::parse(">=10").unwrap().allows_any(&range)
+    } else {
+        false
+    }
+}
+
+/// `requested` without the leading `v` versions are often written with, e.g. `v18.19.0`
+///
+/// Version directories never have one, so this is applied wherever a version is read from
+/// the user. It's only stripped in front of a digit, so an alias like `vite` is left alone.
+pub(crate) fn strip_v(requested: &str) -> &str {
+    match requested.strip_prefix('v') {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+        _ => requested,
+    }
+}
+
+/// Follow `requested` through the alias file, unless it's a version
+///
+/// An exact version (`18.19.0`) is taken as it is and a partial one (`18`, `18.17`) as the
+/// range it stands for, so neither can be shadowed by an alias.
+pub(crate) fn unalias(config: &VoltConfig, requested: &str) -> Result<String> {
+    let bare = strip_v(requested);
+    if bare.parse::<Version>().is_ok() || partial_range(requested).is_some() {
+        return Ok(bare.to_string());
+    }
+
+    alias::resolve(config, requested)
+}
+
+/// A version missing its minor or patch, e.g. `18` or `v18.17`, as the range of releases it
+/// stands for
+pub(crate) fn partial_range(requested: &str) -> Option<Range> {
+    let bare = strip_v(requested);
+    let parts = bare.split('.').collect::<Vec<_>>();
+    let is_partial = parts.len() <= 2
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+
+    if is_partial {
+        bare.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// The release index sorted newest first, so many requests can be resolved against it
+/// without scanning the whole index for each one
+pub(crate) struct Releases<'a> {
+    index: &'a [NodeVersion],
+    /// Newest first
+    sorted: Vec<&'a NodeVersion>,
+}
+
+impl<'a> Releases<'a> {
+    pub(crate) fn new(index: &'a [NodeVersion]) -> Self {
+        let mut sorted = index.iter().collect::<Vec<_>>();
+        sorted.sort_by(|a, b| b.version.cmp(&a.version));
+
+        Self { index, sorted }
+    }
+
+    /// The release of exactly `version`
+    pub(crate) fn get(&self, version: &Version) -> Option<&'a NodeVersion> {
+        let i = self
+            .sorted
+            .binary_search_by(|n| version.cmp(&n.version))
+            .ok()?;
+
+        // Build metadata doesn't count towards the order, but it does make a different version
+        Some(self.sorted[i]).filter(|n| &n.version == version)
+    }
+
+    /// The newest release in `range`, the first match since they're sorted newest first
+    fn newest(&self, range: &Range) -> Option<&'a NodeVersion> {
+        self.sorted
+            .iter()
+            .copied()
+            .find(|n| n.version.satisfies(range))
+    }
+
+    /// Pick the release `requested` stands for, the version itself or the newest match of a
+    /// range
+    ///
+    /// A partial version like `18` or `18.17` picks the newest release in that line.
+    pub(crate) fn resolve(&self, requested: &str) -> Result<Version> {
+        if let Ok(version) = requested.parse::<Version>() {
+            if self.get(&version).is_some() {
+                tracing::debug!("found version '{}'", requested);
+                return Ok(version);
+            }
+
+            let help = match closest_version(self.index, &version) {
+                Some(closest) => format!("did you mean {closest}?"),
+                None => "run `volt node list --remote` to see every available version".to_string(),
+            };
+
+            return Err(VoltError::NodeUnknownVersion {
+                version: requested.to_string(),
+                help,
+            }
+            .into());
+        }
+
+        let range = match partial_range(requested) {
+            Some(range) => range,
+            None => requested
+                .parse::<Range>()
+                .map_err(|_| VoltError::NodeInvalidVersion {
+                    version: requested.to_string(),
+                })?,
+        };
+
+        self.newest(&range)
+            .map(|n| n.version.clone())
+            .ok_or_else(|| {
+                VoltError::NodeNoVersionSatisfies {
+                    range: requested.to_string(),
+                }
+                .into()
+            })
+    }
+}
+
+/// Find the available version in the same major line that's nearest to `version`,
+/// preferring the closest minor and then the closest patch
+fn closest_version<'a>(node_versions: &'a [NodeVersion], version: &Version) -> Option<&'a Version> {
+    let distance = |a: u64, b: u64| a.max(b) - a.min(b);
+
+    node_versions
+        .iter()
+        .map(|n| &n.version)
+        .filter(|v| v.major == version.major)
+        .min_by_key(|v| {
+            (
+                distance(v.minor, version.minor),
+                distance(v.patch, version.patch),
+                // On a tie, suggest the newer release
+                std::cmp::Reverse(*v),
+            )
+        })
+}
+
+/// Find the newest LTS release, optionally restricted to a codename (case-insensitive)
+pub(crate) fn latest_lts(node_versions: &[NodeVersion], codename: Option<&str>) -> Result<Version> {
+    node_versions
+        .iter()
+        .filter(|n| match (&n.lts, codename) {
+            (Some(lts), Some(codename)) => lts.eq_ignore_ascii_case(codename),
+            (Some(_), None) => true,
+            (None, _) => false,
+        })
+        .map(|n| n.version.clone())
+        .max()
+        .ok_or_else(|| {
+            VoltError::NodeLtsNotFound {
+                codename: codename.unwrap_or("*").to_string(),
+            }
+            .into()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_versions_are_ranges() {
+        let index: Vec<NodeVersion> = serde_json::from_value(serde_json::json!([
+            { "version": "v18.17.1", "lts": "Hydrogen", "files": [] },
+            { "version": "v18.19.0", "lts": "Hydrogen", "files": [] },
+            { "version": "v20.10.0", "lts": false, "files": [] },
+        ]))
+        .unwrap();
+        let releases = Releases::new(&index);
+        let resolve = |requested| releases.resolve(requested).unwrap().to_string();
+
+        assert_eq!(resolve("18"), "18.19.0");
+        assert_eq!(resolve("v18.17"), "18.17.1");
+        assert_eq!(resolve("18.17.1"), "18.17.1");
+        assert_eq!(resolve(">=18 <20"), "18.19.0");
+        assert!(releases.resolve("18.18.0").is_err());
+        assert!(partial_range("18.x").is_none());
+        assert!(partial_range("18.17.1").is_none());
+    }
+
+    #[test]
+    fn leading_v_is_only_stripped_from_versions() {
+        assert_eq!(strip_v("v18.19.0"), "18.19.0");
+        assert_eq!(strip_v("v18"), "18");
+        assert_eq!(strip_v("18.19.0"), "18.19.0");
+        assert_eq!(strip_v("vite"), "vite");
+    }
+}
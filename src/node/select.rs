@@ -0,0 +1,277 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! Point `current` at a version and link its binaries where they're found on `PATH`
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use miette::{IntoDiagnostic, Result, WrapErr};
+
+#[cfg(windows)]
+use crate::commands::node::windows_path;
+use crate::{cli::VoltConfig, core::utils::errors::VoltError};
+
+/// What switching to another version did
+pub(crate) struct Switched {
+    /// Whether there was no selected version before
+    pub first: bool,
+    /// Links created in the link directory, and the binaries they point at
+    pub linked: Vec<(PathBuf, PathBuf)>,
+    /// Links that couldn't be removed or created
+    pub failed: BTreeMap<PathBuf, std::io::Error>,
+    /// Whether the link directory was added to the user's PATH, which only new terminals see
+    ///
+    /// Only on windows, elsewhere it's left to the user's shell profile.
+    pub path_updated: bool,
+}
+
+/// Select the installed `version`, linking its binaries into the link directory
+///
+/// This doesn't lock the data directory, the caller has to hold the lock.
+pub(crate) fn select(config: &VoltConfig, version: &str, force: bool) -> Result<Switched> {
+    if !config.node_dir()?.join(version).exists() {
+        return Err(VoltError::NodeVersionNotInstalled {
+            version: version.to_string(),
+        }
+        .into());
+    }
+
+    #[cfg(unix)]
+    return switch_to(config, version, force);
+
+    #[cfg(windows)]
+    return use_windows(config, version);
+}
+
+/// Point `current` at the installed `version` and link its binaries into the link directory
+///
+/// Links from the previous version are removed first. Files volt didn't create are left alone
+/// unless `force` is set, and failing to (un)link one binary doesn't stop the others.
+#[cfg(unix)]
+fn switch_to(config: &VoltConfig, version: &str, force: bool) -> Result<Switched> {
+    let node_dir = config.node_dir()?;
+    let link_dir = config.bin_dir()?;
+    std::fs::create_dir_all(&link_dir).map_err(VoltError::CreateDirError)?;
+
+    let to_install = node_dir.join(version).join("bin");
+    let current = config.current_marker()?;
+
+    // Binaries we couldn't (un)link
+    let mut failed = BTreeMap::new();
+
+    // `current` may dangle if its version was removed, so don't follow it
+    let first = std::fs::symlink_metadata(&current).is_err();
+    if !first {
+        // Remove all the currently installed links, anything else with the same name only
+        // matters if the new version needs its place
+        if let Ok(entries) = std::fs::read_dir(&current) {
+            for entry in entries.flatten() {
+                let installed = link_dir.join(entry.file_name());
+                match remove_link(&installed, &node_dir, force) {
+                    Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => {
+                        failed.insert(installed, e);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Remove the old link
+        std::fs::remove_file(&current)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "failed to remove stale symlink {}",
+                current.display()
+            ))?;
+    }
+
+    // Make a new one to the currently installed version
+    std::os::unix::fs::symlink(&to_install, &current)
+        .into_diagnostic()
+        .wrap_err(format!(
+            "failed to link {} to {}",
+            current.display(),
+            to_install.display()
+        ))?;
+
+    let binaries = std::fs::read_dir(&to_install).map_err(|e| VoltError::ReadFileError {
+        source: e,
+        name: to_install.display().to_string(),
+    })?;
+
+    let mut linked = vec![];
+    for entry in binaries.flatten() {
+        let original = entry.path();
+        let link = link_dir.join(entry.file_name());
+
+        match remove_link(&link, &node_dir, force)
+            .and_then(|_| std::os::unix::fs::symlink(&original, &link))
+        {
+            Ok(_) => {
+                failed.remove(&link);
+                linked.push((link, original));
+            }
+            Err(e) => {
+                failed.insert(link, e);
+            }
+        };
+    }
+
+    Ok(Switched {
+        first,
+        linked,
+        failed,
+        path_updated: false,
+    })
+}
+
+/// Remove the link at `path` if volt made it, i.e. it's a symlink into `node_dir`
+///
+/// Real files and foreign symlinks are only removed with `force`, otherwise this fails
+/// with [`std::io::ErrorKind::AlreadyExists`].
+#[cfg(unix)]
+pub(crate) fn remove_link(path: &Path, node_dir: &Path, force: bool) -> std::io::Result<()> {
+    match std::fs::read_link(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Ok(target) if target.starts_with(node_dir) => std::fs::remove_file(path),
+        _ if force => std::fs::remove_file(path),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            "not created by volt, pass `--force` to replace it",
+        )),
+    }
+}
+
+/// Extensions of the binaries windows releases keep at the root of the version directory
+#[cfg(windows)]
+const WINDOWS_SHIM_EXTENSIONS: &[&str] = &["exe", "cmd"];
+
+/// The binaries and `.cmd` shims (`node.exe`, `npm.cmd`, ...) of a windows install
+#[cfg(windows)]
+pub(crate) fn windows_shims(version_dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(version_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .and_then(|ext| ext.to_str())
+                            .map_or(false, |ext| WINDOWS_SHIM_EXTENSIONS.contains(&ext))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Expose `shim` as `link`, symlinking where allowed and copying otherwise
+#[cfg(windows)]
+fn link_windows_shim(shim: &Path, link: &Path) -> std::io::Result<()> {
+    // `.cmd` shims find their scripts relative to their own location, so a copy or symlink
+    // in another directory would break, forward to the original instead
+    if shim.extension().map_or(false, |ext| ext == "cmd") {
+        return std::fs::write(link, format!("@\"{}\" %*\r\n", shim.display()));
+    }
+
+    std::os::windows::fs::symlink_file(shim, link)
+        .or_else(|_| std::fs::copy(shim, link).map(|_| ()))
+}
+
+/// Point `current` at the installed `version` and link its shims into the link directory,
+/// adding the link directory to the user's PATH if it isn't on it
+#[cfg(windows)]
+fn use_windows(config: &VoltConfig, version: &str) -> Result<Switched> {
+    let node_dir = config.node_dir()?;
+    let bin_dir = config.bin_dir()?;
+    let current = config.current_marker()?;
+
+    let version_dir = node_dir.join(version);
+    if !version_dir.join("node.exe").exists() {
+        return Err(VoltError::NodeVersionNotInstalled {
+            version: version.to_string(),
+        }
+        .into());
+    }
+
+    std::fs::create_dir_all(&bin_dir).map_err(VoltError::CreateDirError)?;
+
+    // Remove the previous version's shims, so none of them outlive the switch
+    let previous = std::fs::read_to_string(&current);
+    let first = previous.is_err();
+    if let Ok(previous) = previous {
+        for shim in windows_shims(&node_dir.join(previous.trim())) {
+            let _ = std::fs::remove_file(bin_dir.join(shim.file_name().unwrap()));
+        }
+    }
+
+    let mut linked = vec![];
+    for shim in windows_shims(&version_dir) {
+        let link = bin_dir.join(shim.file_name().unwrap());
+        let _ = std::fs::remove_file(&link);
+
+        link_windows_shim(&shim, &link)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "failed to link {} to {}",
+                link.display(),
+                shim.display()
+            ))?;
+        linked.push((link, shim));
+    }
+
+    std::fs::write(&current, version).map_err(|e| VoltError::WriteFileError {
+        source: e,
+        name: current.display().to_string(),
+    })?;
+
+    // Split into entries, a substring match would take `C:\bin2` for `C:\bin`
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let path_updated = !windows_path::contains(&path.to_string_lossy(), &bin_dir)
+        && windows_path::add_to_user_path(&bin_dir)
+            .into_diagnostic()
+            .wrap_err(format!("failed to add {} to PATH", bin_dir.display()))?;
+
+    Ok(Switched {
+        first,
+        linked,
+        failed: BTreeMap::new(),
+        path_updated,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_link_keeps_files_volt_did_not_create() {
+        let dir = tempfile::tempdir().unwrap();
+        let wrapper = dir.path().join("npx");
+        std::fs::write(&wrapper, "#!/bin/sh\n").unwrap();
+
+        let err = remove_link(&wrapper, dir.path(), false).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert!(wrapper.exists());
+
+        remove_link(&wrapper, dir.path(), true).unwrap();
+        assert!(!wrapper.exists());
+    }
+}
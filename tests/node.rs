@@ -0,0 +1,610 @@
+/*
+ *    Copyright 2021 Volt Contributors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! `volt node` against a mirror served from the test process
+
+// Releases for unix are `.tar.xz`s, which is what the fixture builds
+#![cfg(unix)]
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    process::{Command, Output},
+    sync::{Arc, Mutex},
+};
+
+use sha2::{Digest, Sha256};
+
+const VERSION: &str = "18.19.0";
+
+/// Name node releases use for this platform, e.g. `linux-x64`
+fn target() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        "x86" => "x86",
+        "arm" => "armv7l",
+        arch => arch,
+    };
+
+    format!("{os}-{arch}")
+}
+
+/// An uncompressed release tarball whose `node` is a script printing its version
+fn tar(version: &str) -> (String, Vec<u8>) {
+    let name = format!("node-v{version}-{}", target());
+    let script = format!("#!/bin/sh\necho v{version}\n");
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(script.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+
+    let mut builder = tar::Builder::new(Vec::new());
+    builder
+        .append_data(&mut header, format!("{name}/bin/node"), script.as_bytes())
+        .unwrap();
+    (name, builder.into_inner().unwrap())
+}
+
+/// The release's `.tar.xz`
+fn tarball(version: &str) -> (String, Vec<u8>) {
+    let (name, tar) = tar(version);
+    (format!("{name}.tar.xz"), lzma::compress(&tar, 6).unwrap())
+}
+
+/// The release's `.tar.gz`
+fn gz_tarball(version: &str) -> (String, Vec<u8>) {
+    let (name, tar) = tar(version);
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&tar).unwrap();
+    (format!("{name}.tar.gz"), encoder.finish().unwrap())
+}
+
+fn shasum(artifact: &str, content: &[u8]) -> String {
+    format!("{}  {artifact}\n", hex::encode(Sha256::digest(content)))
+}
+
+/// The files of a mirror with a single release of `version`
+fn mirror_files(version: &str) -> HashMap<String, Vec<u8>> {
+    let (artifact, content) = tarball(version);
+    let (gz_artifact, gz_content) = gz_tarball(version);
+    let shasums = shasum(&artifact, &content) + &shasum(&gz_artifact, &gz_content);
+
+    // `index.json` lists macos builds as `osx-<arch>-tar`
+    let key = if cfg!(target_os = "macos") {
+        target().replace("darwin", "osx") + "-tar"
+    } else {
+        target()
+    };
+    let index = serde_json::json!([{
+        "version": format!("v{version}"),
+        "date": "2023-11-29",
+        "files": [key],
+        "lts": "Hydrogen",
+    }]);
+
+    let mut files = HashMap::new();
+    files.insert("/index.json".to_string(), index.to_string().into_bytes());
+    files.insert(format!("/v{version}/SHASUMS256.txt"), shasums.into_bytes());
+    files.insert(format!("/v{version}/{artifact}"), content);
+    files.insert(format!("/v{version}/{gz_artifact}"), gz_content);
+    files
+}
+
+/// Each request the server answered, as `<path> <status code>`
+type Log = Arc<Mutex<Vec<String>>>;
+
+/// Serve `files` over HTTP on a free port, returning the base URL
+fn serve(files: HashMap<String, Vec<u8>>) -> String {
+    serve_logged(files).0
+}
+
+/// Serve `files` like [`serve`], along with a log of the requests
+fn serve_logged(files: HashMap<String, Vec<u8>>) -> (String, Log) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let files = Arc::new(files);
+    let log = Log::default();
+
+    let requests = log.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let files = files.clone();
+            let log = requests.clone();
+            std::thread::spawn(move || respond(stream, &files, &log));
+        }
+    });
+
+    (url, log)
+}
+
+/// Files are tagged with a hash of their content
+fn etag(body: &[u8]) -> String {
+    format!("\"{}\"", &hex::encode(Sha256::digest(body))[..16])
+}
+
+fn respond(mut stream: TcpStream, files: &HashMap<String, Vec<u8>>, log: &Log) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request = String::new();
+    reader.read_line(&mut request).unwrap();
+
+    // Only `Range: bytes=<start>-` and `If-None-Match` matter out of the headers
+    let mut start = None;
+    let mut if_none_match = None;
+    let mut header = String::new();
+    while reader.read_line(&mut header).unwrap() > 2 {
+        let value = header.to_ascii_lowercase();
+        if let Some(range) = value.trim().strip_prefix("range: bytes=") {
+            start = range.trim_end_matches('-').parse::<usize>().ok();
+        }
+        if let Some(tag) = value.trim().strip_prefix("if-none-match: ") {
+            if_none_match = Some(tag.to_string());
+        }
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let (status, body) = match (files.get(path), start) {
+        (Some(body), None) if if_none_match == Some(etag(body)) => ("304 Not Modified", &[][..]),
+        (Some(body), Some(start)) if start < body.len() => ("206 Partial Content", &body[start..]),
+        (Some(_), Some(_)) => ("416 Range Not Satisfiable", &[][..]),
+        (Some(body), None) => ("200 OK", &body[..]),
+        (None, _) => ("404 Not Found", &[][..]),
+    };
+
+    log.lock().unwrap().push(format!("{path} {}", &status[..3]));
+
+    let tag = files.get(path).map(|body| etag(body)).unwrap_or_default();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nETag: {tag}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    if method != "HEAD" {
+        let _ = stream.write_all(body);
+    }
+}
+
+/// Run volt with its data directory and home in `home`, against `mirror`
+fn volt(home: &Path, mirror: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_volt"))
+        .args(args)
+        .env("HOME", home)
+        .env("VOLT_HOME", home.join("volt"))
+        .env("VOLT_NODE_MIRROR", mirror)
+        .env_remove("VOLT_NODE_MIRRORS")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn install_then_list() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+
+    let install = volt(home.path(), &mirror, &["node", "install", VERSION]);
+    assert!(
+        install.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&install.stderr)
+    );
+
+    let node = home.path().join("volt/node").join(VERSION).join("bin/node");
+    assert!(node.is_file(), "{} wasn't installed", node.display());
+
+    let meta = home
+        .path()
+        .join("volt/node")
+        .join(VERSION)
+        .join(".volt-meta.json");
+    let meta: serde_json::Value = serde_json::from_slice(&std::fs::read(meta).unwrap()).unwrap();
+    assert_eq!(meta["version"], VERSION);
+    assert_eq!(
+        meta["sha256"],
+        hex::encode(Sha256::digest(&tarball(VERSION).1))
+    );
+
+    let list = volt(home.path(), &mirror, &["node", "list", "--plain"]);
+    assert!(list.status.success());
+    assert_eq!(String::from_utf8_lossy(&list.stdout).trim(), VERSION);
+}
+
+#[test]
+fn installing_an_unknown_version_fails() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+
+    let install = volt(home.path(), &mirror, &["node", "install", "99.0.0"]);
+
+    assert!(!install.status.success());
+    assert!(!home.path().join("volt/node/99.0.0").exists());
+}
+
+#[test]
+fn corrupt_download_is_rejected() {
+    let home = tempfile::tempdir().unwrap();
+    let mut files = mirror_files(VERSION);
+    let (artifact, _) = tarball(VERSION);
+    files.insert(format!("/v{VERSION}/{artifact}"), b"not a tarball".to_vec());
+    let mirror = serve(files);
+
+    let install = volt(home.path(), &mirror, &["node", "install", VERSION]);
+
+    assert!(!install.status.success());
+    assert!(!home.path().join("volt/node").join(VERSION).exists());
+}
+
+#[test]
+fn undecodable_xz_falls_back_to_gz() {
+    let home = tempfile::tempdir().unwrap();
+    let mut files = mirror_files(VERSION);
+
+    // Checksums match, so only decompressing the `.tar.xz` fails
+    let (artifact, _) = tarball(VERSION);
+    let (gz_artifact, gz_content) = gz_tarball(VERSION);
+    let garbage = b"not xz".to_vec();
+    let shasums = shasum(&artifact, &garbage) + &shasum(&gz_artifact, &gz_content);
+    files.insert(format!("/v{VERSION}/{artifact}"), garbage);
+    files.insert(format!("/v{VERSION}/SHASUMS256.txt"), shasums.into_bytes());
+    let mirror = serve(files);
+
+    let install = volt(home.path(), &mirror, &["node", "install", VERSION]);
+
+    let stderr = String::from_utf8_lossy(&install.stderr);
+    assert!(install.status.success(), "install failed: {stderr}");
+    assert!(stderr.contains(&format!("trying {gz_artifact} instead")));
+    assert!(home
+        .path()
+        .join("volt/node")
+        .join(VERSION)
+        .join("bin/node")
+        .is_file());
+}
+
+#[test]
+fn interrupted_download_is_resumed() {
+    let home = tempfile::tempdir().unwrap();
+    let (artifact, content) = tarball(VERSION);
+    let half = content.len() / 2;
+
+    // Only the second half of the mirror's copy is right, so the download has to be resumed
+    let mut files = mirror_files(VERSION);
+    let mut served = vec![0; half];
+    served.extend_from_slice(&content[half..]);
+    files.insert(format!("/v{VERSION}/{artifact}"), served);
+    let mirror = serve(files);
+
+    let downloads = home.path().join("volt/downloads");
+    std::fs::create_dir_all(&downloads).unwrap();
+    std::fs::write(downloads.join(format!("{artifact}.part")), &content[..half]).unwrap();
+
+    let install = volt(home.path(), &mirror, &["node", "install", VERSION]);
+
+    let stderr = String::from_utf8_lossy(&install.stderr);
+    assert!(install.status.success(), "install failed: {stderr}");
+    assert!(home
+        .path()
+        .join("volt/node")
+        .join(VERSION)
+        .join("bin/node")
+        .is_file());
+    assert_eq!(std::fs::read_dir(&downloads).unwrap().count(), 0);
+}
+
+#[test]
+fn porcelain_prints_only_the_version() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+
+    let install = volt(
+        home.path(),
+        &mirror,
+        &["node", "install", "--lts", "--porcelain"],
+    );
+
+    assert!(install.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&install.stdout),
+        format!("{VERSION}\n")
+    );
+}
+
+#[test]
+fn expired_index_is_revalidated() {
+    let home = tempfile::tempdir().unwrap();
+    let (mirror, log) = serve_logged(mirror_files(VERSION));
+    let list = |args: &[&str]| {
+        let args = [&["--index-ttl", "0", "node", "list", "--remote"], args].concat();
+        let list = volt(home.path(), &mirror, &args);
+        assert!(list.status.success());
+        assert!(String::from_utf8_lossy(&list.stdout).contains(VERSION));
+    };
+
+    list(&[]);
+    list(&[]);
+    list(&["--refresh"]);
+
+    // The second listing only asks whether the index changed, `--refresh` downloads it again
+    let requests = log.lock().unwrap().clone();
+    assert_eq!(
+        requests,
+        ["/index.json 200", "/index.json 304", "/index.json 200"]
+    );
+}
+
+#[test]
+fn download_only_saves_the_verified_tarball() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+    let dir = home.path().join("artifacts");
+
+    let install = volt(
+        home.path(),
+        &mirror,
+        &[
+            "node",
+            "install",
+            VERSION,
+            "--porcelain",
+            "--download-only",
+            dir.to_str().unwrap(),
+        ],
+    );
+
+    assert!(install.status.success());
+    let (artifact, content) = tarball(VERSION);
+    let saved = dir.join(artifact);
+    assert_eq!(
+        String::from_utf8_lossy(&install.stdout).trim(),
+        saved.display().to_string()
+    );
+    assert_eq!(std::fs::read(saved).unwrap(), content);
+    assert!(!home.path().join("volt/node").join(VERSION).exists());
+}
+
+#[test]
+fn dry_run_installs_nothing() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+
+    let install = volt(
+        home.path(),
+        &mirror,
+        &["node", "install", "18", "--dry-run"],
+    );
+
+    assert!(install.status.success());
+    let (artifact, _) = tarball(VERSION);
+    let stdout = String::from_utf8_lossy(&install.stdout);
+    assert!(stdout.contains(&format!("Would download {mirror}/v{VERSION}/{artifact}")));
+    assert!(!home.path().join("volt/node").exists());
+    assert!(!home.path().join("volt/downloads").exists());
+}
+
+#[test]
+fn remote_list_as_json() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+
+    let list = volt(
+        home.path(),
+        &mirror,
+        &["node", "list", "--remote", "--json"],
+    );
+
+    assert!(list.status.success());
+    let list: serde_json::Value = serde_json::from_slice(&list.stdout).unwrap();
+    assert_eq!(list[0]["version"], VERSION);
+    assert_eq!(list[0]["lts"], "Hydrogen");
+    assert_eq!(list[0]["date"], "2023-11-29");
+    assert_eq!(list[0]["installed"], false);
+}
+
+#[test]
+fn leading_v_is_ignored() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+    let v = format!("v{VERSION}");
+    let run = |args: &[&str]| {
+        let output = volt(home.path(), &mirror, args);
+        assert!(
+            output.status.success(),
+            "{args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    };
+    let version_dir = home.path().join("volt/node").join(VERSION);
+
+    run(&["node", "install", &v]);
+    assert!(version_dir.is_dir());
+    assert!(!home.path().join("volt/node").join(&v).exists());
+
+    run(&["node", "use", &v]);
+    run(&["node", "use", VERSION]);
+
+    run(&["node", "remove", &v, "--force", "--yes"]);
+    assert!(!version_dir.exists());
+}
+
+#[test]
+fn bare_node_prints_a_summary() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+
+    let summary = volt(home.path(), &mirror, &["node"]);
+
+    assert!(summary.status.success());
+    assert!(String::from_utf8_lossy(&summary.stdout).contains("No versions installed"));
+}
+
+#[test]
+fn migrate_moves_versions_and_relinks() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+
+    let install = volt(home.path(), &mirror, &["node", "install", VERSION]);
+    assert!(install.status.success());
+    let select = volt(home.path(), &mirror, &["node", "use", VERSION]);
+    assert!(select.status.success());
+
+    // As if VOLT_HOME used to point somewhere else
+    let old = home.path().join("old");
+    std::fs::rename(home.path().join("volt"), &old).unwrap();
+
+    let migrate = volt(
+        home.path(),
+        &mirror,
+        &["node", "migrate", old.to_str().unwrap()],
+    );
+    assert!(
+        migrate.status.success(),
+        "migrate failed: {}",
+        String::from_utf8_lossy(&migrate.stderr)
+    );
+
+    let version_dir = home.path().join("volt/node").join(VERSION);
+    assert!(version_dir.join("bin/node").is_file());
+    assert!(!old.join("node").join(VERSION).exists());
+    assert_eq!(
+        std::fs::read_link(home.path().join(".local/bin/node")).unwrap(),
+        version_dir.join("bin/node")
+    );
+
+    // Nothing is left to move
+    let again = volt(
+        home.path(),
+        &mirror,
+        &["node", "migrate", old.to_str().unwrap()],
+    );
+    assert!(!again.status.success());
+}
+
+#[test]
+fn offline_never_asks_the_mirror() {
+    let home = tempfile::tempdir().unwrap();
+    let (mirror, log) = serve_logged(mirror_files(VERSION));
+
+    // Without a cached index there's nothing to resolve against
+    let uncached = volt(
+        home.path(),
+        &mirror,
+        &["--offline", "node", "install", VERSION],
+    );
+    assert!(!uncached.status.success());
+
+    let list = volt(home.path(), &mirror, &["node", "list", "--remote"]);
+    assert!(list.status.success());
+    let missing = volt(
+        home.path(),
+        &mirror,
+        &["--offline", "node", "install", VERSION],
+    );
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("--offline"));
+
+    // A tarball left in the downloads directory is installed without being downloaded
+    let (artifact, content) = tarball(VERSION);
+    let downloads = home.path().join("volt/downloads");
+    std::fs::create_dir_all(&downloads).unwrap();
+    std::fs::write(downloads.join(artifact), content).unwrap();
+    let install = volt(
+        home.path(),
+        &mirror,
+        &["--offline", "node", "install", "18"],
+    );
+    assert!(
+        install.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&install.stderr)
+    );
+    assert!(home
+        .path()
+        .join("volt/node")
+        .join(VERSION)
+        .join("bin/node")
+        .is_file());
+
+    assert_eq!(log.lock().unwrap().clone(), ["/index.json 200"]);
+}
+
+#[test]
+fn doctor_flags_a_mislabeled_version() {
+    let home = tempfile::tempdir().unwrap();
+    let mirror = serve(mirror_files(VERSION));
+
+    let install = volt(home.path(), &mirror, &["node", "install", VERSION]);
+    assert!(install.status.success());
+
+    let doctor = volt(home.path(), &mirror, &["node", "doctor"]);
+    let report = String::from_utf8_lossy(&doctor.stdout);
+    assert!(report.contains(&format!("✓ node {VERSION}: v{VERSION}")));
+
+    // As if another release had been unpacked into the directory
+    let node_dir = home.path().join("volt/node");
+    std::fs::rename(node_dir.join(VERSION), node_dir.join("18.18.0")).unwrap();
+
+    let doctor = volt(home.path(), &mirror, &["node", "doctor"]);
+    assert!(!doctor.status.success());
+    let report = String::from_utf8_lossy(&doctor.stdout);
+    assert!(report.contains(&format!(
+        "✗ node 18.18.0: `node --version` reported `v{VERSION}`"
+    )));
+}
+
+#[tokio::test]
+async fn library_install_needs_a_build_for_this_platform() {
+    use clap::Parser;
+
+    let home = tempfile::tempdir().unwrap();
+    let mut files = mirror_files(VERSION);
+    let index = serde_json::json!([{
+        "version": format!("v{VERSION}"),
+        "lts": "Hydrogen",
+        "files": ["aix-ppc64"],
+    }]);
+    files.insert("/index.json".to_string(), index.to_string().into_bytes());
+    let (mirror, log) = serve_logged(files);
+
+    let data_dir = home.path().join("volt");
+    let config = volt::cli::VoltConfig::parse_from([
+        "volt",
+        "--data-dir",
+        data_dir.to_str().unwrap(),
+        "--node-mirror",
+        &mirror,
+    ]);
+
+    let version = VERSION.parse().unwrap();
+    let err = volt::node::install(&config, &version).await.unwrap_err();
+    assert!(err.to_string().contains("has no"), "{err}");
+    assert!(!data_dir.join("node").join(VERSION).exists());
+
+    // Nothing past the index was asked for
+    assert!(log
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|r| r.starts_with("/index.json")));
+}